@@ -0,0 +1,62 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use pastel::distinct::{
+    ColorConstraints, DistanceMetric, OptimizationMode, OptimizationTarget, SimulatedAnnealing,
+    SimulationParameters,
+};
+use pastel::random::{strategies, RandomizationStrategy};
+use pastel::Color;
+use rand::SeedableRng;
+use rand_xoshiro::Xoshiro256StarStar;
+
+const NUM_COLORS: usize = 64;
+const NUM_ITERATIONS: usize = 2_000;
+
+fn initial_colors() -> Vec<Color> {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    (0..NUM_COLORS)
+        .map(|_| strategies::UniformRGB.generate_with(&mut rng))
+        .collect()
+}
+
+fn run_annealing(colors: &[Color], distance_metric: DistanceMetric, opt_mode: OptimizationMode) {
+    let mut annealing = SimulatedAnnealing::with_rng(
+        colors,
+        SimulationParameters {
+            initial_temperature: 3.0,
+            cooling_rate: 0.95,
+            num_iterations: NUM_ITERATIONS,
+            opt_target: OptimizationTarget::Mean,
+            opt_mode,
+            distance_metric,
+            num_fixed_colors: 0,
+            constraints: ColorConstraints::default(),
+        },
+        Xoshiro256StarStar::seed_from_u64(21),
+    );
+    annealing.run(&mut |_| {});
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let colors = initial_colors();
+
+    let mut group = c.benchmark_group("distinct");
+    group.throughput(Throughput::Elements(NUM_ITERATIONS as u64));
+
+    group.bench_function("anneal_64_cie76_global", |b| {
+        b.iter(|| run_annealing(&colors, DistanceMetric::CIE76, OptimizationMode::Global))
+    });
+    group.bench_function("anneal_64_cie76_local", |b| {
+        b.iter(|| run_annealing(&colors, DistanceMetric::CIE76, OptimizationMode::Local))
+    });
+    group.bench_function("anneal_64_ciede2000_global", |b| {
+        b.iter(|| run_annealing(&colors, DistanceMetric::CIEDE2000, OptimizationMode::Global))
+    });
+    group.bench_function("anneal_64_ciede2000_local", |b| {
+        b.iter(|| run_annealing(&colors, DistanceMetric::CIEDE2000, OptimizationMode::Local))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use pastel::random::{strategies, RandomizationStrategy};
+use rand::SeedableRng;
+use rand_xoshiro::Xoshiro256StarStar;
+
+const NUM_COLORS: usize = 100_000;
+
+/// One random hex color per line.
+fn hex_colors() -> String {
+    let mut rng = Xoshiro256StarStar::seed_from_u64(42);
+    (0..NUM_COLORS)
+        .map(|_| strategies::UniformRGB.generate_with(&mut rng))
+        .map(|color| format!("{}\n", color.to_rgb_hex_string(true)))
+        .collect()
+}
+
+/// Run the pastel binary with the given input on standard input (end-to-end throughput).
+fn pastel(args: &[&str], input: &[u8]) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pastel"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("pastel binary can be executed");
+
+    child
+        .stdin
+        .take()
+        .expect("standard input is piped")
+        .write_all(input)
+        .expect("input can be written");
+
+    assert!(child.wait().expect("pastel is running").success());
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let colors = hex_colors();
+    let csv: String = colors
+        .lines()
+        .enumerate()
+        .map(|(i, color)| format!("color-{},{},x\n", i, color))
+        .collect();
+
+    let mut group = c.benchmark_group("throughput");
+    group.throughput(Throughput::Elements(NUM_COLORS as u64));
+    group.sample_size(10);
+
+    group.bench_function("format_hsl", |b| {
+        b.iter(|| pastel(&["format", "hsl"], colors.as_bytes()))
+    });
+    group.bench_function("format_hsl_parallel", |b| {
+        b.iter(|| pastel(&["--jobs", "0", "format", "hsl"], colors.as_bytes()))
+    });
+    group.bench_function("lighten", |b| {
+        b.iter(|| pastel(&["lighten", "0.1"], colors.as_bytes()))
+    });
+    group.bench_function("lighten_parallel", |b| {
+        b.iter(|| pastel(&["--jobs", "0", "lighten", "0.1"], colors.as_bytes()))
+    });
+    group.bench_function("format_field", |b| {
+        b.iter(|| {
+            pastel(
+                &["--field", "2", "--delimiter", ",", "format", "rgb"],
+                csv.as_bytes(),
+            )
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,9 +1,17 @@
 use std::borrow::Borrow;
 
 pub use atty::Stream;
+use nom::{
+    branch::alt,
+    bytes::complete::tag_no_case,
+    character::complete::{space0, u8 as ansi_code},
+    combinator::all_consuming,
+    error::context,
+};
 use once_cell::sync::Lazy;
 
 use crate::delta_e::ciede2000;
+use crate::parser::{closing_parenthesis, IResult};
 use crate::{Color, Lab};
 
 static ANSI_LAB_REPRESENTATIONS: Lazy<Vec<(u8, Lab)>> = Lazy::new(|| {
@@ -310,10 +318,42 @@ impl Brush {
     }
 }
 
+fn parse_ansi_8bit(input: &str) -> IResult<&str, Color> {
+    let (input, _) = alt((tag_no_case("ansi-8bit("), tag_no_case("ansi(")))(input)?;
+    let (input, _) = space0(input)?;
+    let (input, code) = context(
+        "expected a color code (an integer between 0 and 255)",
+        ansi_code,
+    )(input)?;
+    let (input, _) = closing_parenthesis(input)?;
+
+    Ok((input, Color::from_ansi_8bit(code)))
+}
+
+/// Parse 8-bit ANSI color codes, written as `ansi(196)` or `ansi-8bit(196)`.
+pub(crate) fn parse_ansi_color(input: &str) -> IResult<&str, Color> {
+    all_consuming(parse_ansi_8bit)(input.trim())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_ansi_syntax() {
+        use crate::parser::parse_color;
+
+        assert_eq!(Some(Color::red()), parse_color("ansi(9)"));
+        assert_eq!(Some(Color::from_rgb(255, 0, 0)), parse_color("ansi(196)"));
+        assert_eq!(
+            Some(Color::from_ansi_8bit(21)),
+            parse_color("ansi-8bit( 21 )")
+        );
+
+        assert_eq!(None, parse_color("ansi(256)"));
+        assert_eq!(None, parse_color("ansi(-1)"));
+    }
+
     #[test]
     fn from_ansi_8bit_lower_16() {
         assert_eq!(Color::black(), Color::from_ansi_8bit(0));
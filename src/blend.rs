@@ -0,0 +1,342 @@
+//! Blend modes from the W3C Compositing and Blending specification.
+//!
+//! See: <https://www.w3.org/TR/compositing-1/#blending>
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::types::{Scalar, Vec3};
+use crate::Color;
+
+/// A function that determines the color of overlapping pixels of a backdrop and a source color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    /// All blend modes, in the order of the specification.
+    pub const ALL: [BlendMode; 16] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+        BlendMode::ColorDodge,
+        BlendMode::ColorBurn,
+        BlendMode::HardLight,
+        BlendMode::SoftLight,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+        BlendMode::Hue,
+        BlendMode::Saturation,
+        BlendMode::Color,
+        BlendMode::Luminosity,
+    ];
+
+    /// The CSS name of the blend mode (e.g. `color-dodge`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+
+    /// Apply the blend function to the (opaque) backdrop and source colors.
+    fn apply(&self, backdrop: Vec3, source: Vec3) -> Vec3 {
+        let separable = |f: fn(Scalar, Scalar) -> Scalar| {
+            [
+                f(backdrop[0], source[0]),
+                f(backdrop[1], source[1]),
+                f(backdrop[2], source[2]),
+            ]
+        };
+
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => separable(multiply),
+            BlendMode::Screen => separable(screen),
+            BlendMode::Overlay => separable(|b, s| hard_light(s, b)),
+            BlendMode::Darken => separable(Scalar::min),
+            BlendMode::Lighten => separable(Scalar::max),
+            BlendMode::ColorDodge => separable(color_dodge),
+            BlendMode::ColorBurn => separable(color_burn),
+            BlendMode::HardLight => separable(hard_light),
+            BlendMode::SoftLight => separable(soft_light),
+            BlendMode::Difference => separable(|b, s| (b - s).abs()),
+            BlendMode::Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+            BlendMode::Hue => set_lum(set_sat(source, sat(backdrop)), lum(backdrop)),
+            BlendMode::Saturation => set_lum(set_sat(backdrop, sat(source)), lum(backdrop)),
+            BlendMode::Color => set_lum(source, lum(backdrop)),
+            BlendMode::Luminosity => set_lum(backdrop, lum(source)),
+        }
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = &'static str;
+
+    /// Parse a blend mode from its CSS name (e.g. `color-dodge`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        BlendMode::ALL
+            .iter()
+            .find(|mode| mode.name() == s)
+            .copied()
+            .ok_or("unknown blend mode")
+    }
+}
+
+fn multiply(b: Scalar, s: Scalar) -> Scalar {
+    b * s
+}
+
+fn screen(b: Scalar, s: Scalar) -> Scalar {
+    b + s - b * s
+}
+
+fn hard_light(b: Scalar, s: Scalar) -> Scalar {
+    if s <= 0.5 {
+        multiply(b, 2.0 * s)
+    } else {
+        screen(b, 2.0 * s - 1.0)
+    }
+}
+
+fn color_dodge(b: Scalar, s: Scalar) -> Scalar {
+    if b == 0.0 {
+        0.0
+    } else if s >= 1.0 {
+        1.0
+    } else {
+        Scalar::min(1.0, b / (1.0 - s))
+    }
+}
+
+fn color_burn(b: Scalar, s: Scalar) -> Scalar {
+    if b >= 1.0 {
+        1.0
+    } else if s == 0.0 {
+        0.0
+    } else {
+        1.0 - Scalar::min(1.0, (1.0 - b) / s)
+    }
+}
+
+fn soft_light(b: Scalar, s: Scalar) -> Scalar {
+    if s <= 0.5 {
+        b - (1.0 - 2.0 * s) * b * (1.0 - b)
+    } else {
+        let d = if b <= 0.25 {
+            ((16.0 * b - 12.0) * b + 4.0) * b
+        } else {
+            b.sqrt()
+        };
+        b + (2.0 * s - 1.0) * (d - b)
+    }
+}
+
+fn lum(c: Vec3) -> Scalar {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(c: Vec3) -> Vec3 {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+
+    let mut c = c;
+    if n < 0.0 {
+        c = [
+            l + (c[0] - l) * l / (l - n),
+            l + (c[1] - l) * l / (l - n),
+            l + (c[2] - l) * l / (l - n),
+        ];
+    }
+    if x > 1.0 {
+        c = [
+            l + (c[0] - l) * (1.0 - l) / (x - l),
+            l + (c[1] - l) * (1.0 - l) / (x - l),
+            l + (c[2] - l) * (1.0 - l) / (x - l),
+        ];
+    }
+    c
+}
+
+fn set_lum(c: Vec3, l: Scalar) -> Vec3 {
+    let d = l - lum(c);
+    clip_color([c[0] + d, c[1] + d, c[2] + d])
+}
+
+fn sat(c: Vec3) -> Scalar {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: Vec3, s: Scalar) -> Vec3 {
+    let mut indices = [0, 1, 2];
+    indices.sort_by(|&i, &j| c[i].partial_cmp(&c[j]).unwrap_or(Ordering::Equal));
+    let [min, mid, max] = indices;
+
+    let mut result = [0.0; 3];
+    if c[max] > c[min] {
+        result[mid] = (c[mid] - c[min]) * s / (c[max] - c[min]);
+        result[max] = s;
+    }
+    result
+}
+
+/// Blend the source color with the backdrop color using the given blend mode, and composite the
+/// result over the backdrop (source-over).
+///
+/// See: <https://www.w3.org/TR/compositing-1/#blending>
+pub fn blend(backdrop: &Color, source: &Color, mode: BlendMode) -> Color {
+    let b = backdrop.to_rgba_float();
+    let s = source.to_rgba_float();
+
+    let blended = mode.apply([b.r, b.g, b.b], [s.r, s.g, s.b]);
+
+    // Cs = (1 - αb) * Cs + αb * B(Cb, Cs), followed by source-over compositing
+    let alpha = s.alpha + b.alpha * (1.0 - s.alpha);
+    let channel = |c_b: Scalar, c_s: Scalar, blended: Scalar| -> Scalar {
+        if alpha == 0.0 {
+            return 0.0;
+        }
+        let c_s = (1.0 - b.alpha) * c_s + b.alpha * blended;
+        (s.alpha * c_s + b.alpha * c_b * (1.0 - s.alpha)) / alpha
+    };
+
+    Color::from_rgba_float(
+        channel(b.r, s.r, blended[0]),
+        channel(b.g, s.g, blended[1]),
+        channel(b.b, s.b, blended[2]),
+        alpha,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helper::assert_almost_equal;
+
+    fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::from_rgb(r, g, b)
+    }
+
+    #[test]
+    fn parse_blend_mode() {
+        assert_eq!(Ok(BlendMode::ColorDodge), "color-dodge".parse());
+        assert_eq!(Ok(BlendMode::Luminosity), "Luminosity".parse());
+        assert!("dissolve".parse::<BlendMode>().is_err());
+
+        for mode in BlendMode::ALL {
+            assert_eq!(Ok(mode), mode.name().parse());
+        }
+    }
+
+    #[test]
+    fn separable_blend_modes() {
+        let backdrop = rgb(255, 128, 0);
+        let source = rgb(0, 128, 255);
+
+        let blend = |mode| blend(&backdrop, &source, mode);
+
+        assert_almost_equal(&source, &blend(BlendMode::Normal));
+        assert_almost_equal(&rgb(0, 64, 0), &blend(BlendMode::Multiply));
+        assert_almost_equal(&rgb(255, 192, 255), &blend(BlendMode::Screen));
+        assert_almost_equal(&rgb(255, 128, 0), &blend(BlendMode::Overlay));
+        assert_almost_equal(&rgb(0, 128, 0), &blend(BlendMode::Darken));
+        assert_almost_equal(&rgb(255, 128, 255), &blend(BlendMode::Lighten));
+        assert_almost_equal(&rgb(255, 0, 255), &blend(BlendMode::Difference));
+        assert_almost_equal(&rgb(255, 128, 255), &blend(BlendMode::Exclusion));
+
+        assert_almost_equal(&rgb(255, 255, 0), &blend(BlendMode::ColorDodge));
+        assert_almost_equal(&rgb(255, 2, 0), &blend(BlendMode::ColorBurn));
+        assert_almost_equal(&rgb(0, 128, 255), &blend(BlendMode::HardLight));
+    }
+
+    #[test]
+    fn soft_light() {
+        let gray = Color::graytone(0.5);
+        let result = blend(&gray, &Color::white(), BlendMode::SoftLight);
+        assert_almost_equal(&rgb(181, 181, 181), &result);
+
+        let result = blend(&gray, &Color::black(), BlendMode::SoftLight);
+        assert_almost_equal(&rgb(64, 64, 64), &result);
+    }
+
+    #[test]
+    fn non_separable_blend_modes() {
+        let gray = Color::graytone(0.5);
+
+        // gray has no hue or saturation
+        assert_almost_equal(&gray, &blend(&gray, &Color::red(), BlendMode::Saturation));
+        assert_almost_equal(&gray, &blend(&gray, &Color::red(), BlendMode::Hue));
+
+        // the luminosity of white and the hue/saturation of red
+        assert_almost_equal(
+            &Color::white(),
+            &blend(&Color::red(), &Color::white(), BlendMode::Luminosity),
+        );
+
+        // color keeps the luminosity of the backdrop
+        let result = blend(&gray, &Color::blue(), BlendMode::Color);
+        assert!((lum_of(&result) - lum_of(&gray)).abs() < 0.01);
+
+        let result = blend(&Color::blue(), &Color::red(), BlendMode::Hue);
+        assert!((lum_of(&result) - lum_of(&Color::blue())).abs() < 0.01);
+        assert!(result.to_rgba().r > result.to_rgba().b);
+    }
+
+    fn lum_of(c: &Color) -> Scalar {
+        let c = c.to_rgba_float();
+        lum([c.r, c.g, c.b])
+    }
+
+    #[test]
+    fn blend_with_alpha() {
+        let backdrop = Color::red();
+        let transparent = Color::from_rgba(0, 0, 255, 0.0);
+
+        for mode in BlendMode::ALL {
+            assert_eq!(backdrop, blend(&backdrop, &transparent, mode));
+        }
+
+        // with a transparent backdrop, the source color is used as is
+        let source = Color::from_rgba(0, 128, 255, 0.5);
+        let transparent_backdrop = Color::from_rgba(255, 0, 0, 0.0);
+        assert_eq!(
+            source,
+            blend(&transparent_backdrop, &source, BlendMode::Multiply)
+        );
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(pastel_normal_build)]
+use clap::builder::PossibleValuesParser;
+use clap::builder::ValueParser;
 use clap::{crate_description, crate_name, crate_version, AppSettings, Arg, Command};
 
 // Only include `colorpicker_tools` for normal builds (not when compiling `build.rs` where
@@ -5,6 +8,21 @@ use clap::{crate_description, crate_name, crate_version, AppSettings, Arg, Comma
 #[cfg(pastel_normal_build)]
 use crate::colorpicker_tools::COLOR_PICKER_TOOL_NAMES;
 
+/// Accepts the names of the output formats of `pastel format`. The format registry is not
+/// available when compiling `build.rs`, so the generated shell completions do not list them.
+#[cfg(pastel_normal_build)]
+fn format_type_parser() -> ValueParser {
+    let format_types = crate::formats::CHANNELS
+        .iter()
+        .map(|channel| channel.names[0]);
+    PossibleValuesParser::new(format_types).into()
+}
+
+#[cfg(not(pastel_normal_build))]
+fn format_type_parser() -> ValueParser {
+    ValueParser::string()
+}
+
 const SORT_OPTIONS: &[&str] = &["brightness", "luminance", "hue", "chroma", "random"];
 const DEFAULT_SORT_ORDER: &str = "hue";
 
@@ -22,13 +40,18 @@ pub fn build_cli() -> Command<'static> {
              \n  - '#778899'\
              \n  - 778899\
              \n  - 789\
+             \n  - 0x778899\
+             \n  - rgb:77/88/99\
              \n  - 'rgb(119, 136, 153)'\
              \n  - '119,136,153'\
              \n  - 'hsl(210, 14.3%, 53.3%)'\n\
              Alpha transparency is also supported:\
              \n  - '#77889980'\
              \n  - 'rgba(119, 136, 153, 0.5)'\
-             \n  - 'hsla(210, 14.3%, 53.3%, 50%)'",
+             \n  - 'hsla(210, 14.3%, 53.3%, 50%)'\n\
+             Colors can also be derived from other colors, as in CSS:\
+             \n  - 'rgb(from lightslategray r g calc(b + 20))'\
+             \n  - 'color-mix(in oklch, lightslategray 40%, blue)'",
         )
         .required(false)
         .multiple_occurrences(true);
@@ -38,10 +61,33 @@ pub fn build_cli() -> Command<'static> {
         .short('s')
         .value_name("name")
         .help("The colorspace in which to interpolate")
-        .possible_values(&["Lab", "LCh", "RGB", "HSL", "HWB"])
+        .possible_values(["Lab", "LCh", "RGB", "sRGB-linear", "HSL", "HWB"])
         .ignore_case(true)
         .default_value("Lab");
 
+    let hue_arg = Arg::new("hue")
+        .long("hue")
+        .value_name("method")
+        .help(
+            "The path along the hue circle for color spaces with a hue component (HSL, HWB, LCh):\n   \
+             shorter:     take the shorter path\n   \
+             longer:      take the longer path, e.g. for rainbow gradients\n   \
+             increasing:  always increase the hue angle\n   \
+             decreasing:  always decrease the hue angle\n\
+             \n\
+             Default method: 'shorter'\n ",
+        )
+        .possible_values(["shorter", "longer", "increasing", "decreasing"])
+        .hide_default_value(true)
+        .hide_possible_values(true)
+        .default_value("shorter");
+
+    let no_premultiply_arg = Arg::new("no-premultiply").long("no-premultiply").help(
+        "Interpolate the alpha channel independently from the color channels. By default, \
+             colors are mixed with premultiplied alpha (as in CSS), such that transparent colors \
+             contribute less to the result.",
+    );
+
     Command::new(crate_name!())
         .version(crate_version!())
         .about(crate_description!())
@@ -95,7 +141,7 @@ pub fn build_cli() -> Command<'static> {
                              \n\
                              Default strategy: 'vivid'\n ",
                         )
-                        .possible_values(&["vivid", "rgb", "gray", "lch_hue"])
+                        .possible_values(["vivid", "rgb", "gray", "lch_hue"])
                         .hide_default_value(true)
                         .hide_possible_values(true)
                         .default_value("vivid"),
@@ -116,10 +162,12 @@ pub fn build_cli() -> Command<'static> {
                 .long_about("Generate a set of visually distinct colors by maximizing \
                              the perceived color difference between pairs of colors.\n\n\
                              The default parameters for the optimization procedure \
-                             (simulated annealing) should work fine for up to 10-20 colors.")
+                             (simulated annealing) should work fine for up to 10-20 colors. \
+                             The greedy algorithm is much faster and yields deterministic \
+                             results.")
                 .arg(
                     Arg::new("number")
-                        .help("Number of distinct colors in the set")
+                        .help("Number of distinct colors in the set (including the fixed colors)")
                         .takes_value(true)
                         .default_value("10")
                         .value_name("count"),
@@ -131,7 +179,7 @@ pub fn build_cli() -> Command<'static> {
                         .help("Distance metric to compute mutual color distances. The CIEDE2000 is \
                                more accurate, but also much slower.")
                         .takes_value(true)
-                        .possible_values(&["CIEDE2000", "CIE76"])
+                        .possible_values(["CIEDE2000", "CIE76"])
                         .value_name("name")
                         .default_value("CIE76")
                 )
@@ -141,6 +189,122 @@ pub fn build_cli() -> Command<'static> {
                         .help("Only show the optimized minimal distance")
                         .hide(true)
                 )
+                .arg(
+                    Arg::new("extend")
+                        .long("extend")
+                        .short('e')
+                        .help("Extend an existing palette by the given number of new colors. The \
+                               existing colors are given by '--colors', read from the file given \
+                               by '--palette', or read from standard input. They are kept \
+                               unchanged and the new colors are appended.")
+                        .takes_value(true)
+                        .value_name("count"),
+                )
+                .arg(
+                    Arg::new("colors")
+                        .long("colors")
+                        .help("The colors of the palette that should be extended")
+                        .takes_value(true)
+                        .multiple_values(true)
+                        .requires("extend")
+                        .conflicts_with("palette")
+                        .value_name("color"),
+                )
+                .arg(
+                    Arg::new("palette")
+                        .long("palette")
+                        .help("Read the palette that should be extended from the given file \
+                               (one color per line)")
+                        .takes_value(true)
+                        .requires("extend")
+                        .value_name("file"),
+                )
+                .arg(
+                    Arg::new("algorithm")
+                        .long("algorithm")
+                        .short('a')
+                        .help(
+                            "Algorithm to generate the colors:\n   \
+                             annealing:  simulated annealing, starting from random colors\n   \
+                             greedy:     greedy farthest-point selection (fast, deterministic)\n   \
+                             hybrid:     simulated annealing, starting from the greedy result\n               \
+                             (restarts start from a k-means++ seeding)\n\
+                             \n\
+                             Default algorithm: 'annealing'\n ",
+                        )
+                        .takes_value(true)
+                        .possible_values(["annealing", "greedy", "hybrid"])
+                        .hide_default_value(true)
+                        .hide_possible_values(true)
+                        .default_value("annealing")
+                        .value_name("name"),
+                )
+                .arg(
+                    Arg::new("restarts")
+                        .long("restarts")
+                        .help("Number of independent optimization runs (performed in parallel). \
+                               The best result is kept. Not used by the greedy algorithm. \
+                               [default: 1]")
+                        .takes_value(true)
+                        .value_name("count"),
+                )
+                .arg(
+                    Arg::new("min-lightness")
+                        .long("min-lightness")
+                        .help("Minimum LCh lightness (between 0 and 100) of the generated colors")
+                        .takes_value(true)
+                        .value_name("L"),
+                )
+                .arg(
+                    Arg::new("max-lightness")
+                        .long("max-lightness")
+                        .help("Maximum LCh lightness (between 0 and 100) of the generated colors")
+                        .takes_value(true)
+                        .value_name("L"),
+                )
+                .arg(
+                    Arg::new("min-chroma")
+                        .long("min-chroma")
+                        .help("Minimum LCh chroma of the generated colors")
+                        .takes_value(true)
+                        .value_name("C"),
+                )
+                .arg(
+                    Arg::new("max-chroma")
+                        .long("max-chroma")
+                        .help("Maximum LCh chroma of the generated colors")
+                        .takes_value(true)
+                        .value_name("C"),
+                )
+                .arg(
+                    Arg::new("exclude-hue")
+                        .long("exclude-hue")
+                        .help("Exclude a range of LCh hues (in degrees) from the generated colors, \
+                               e.g. '330-60' to avoid reddish colors. Can be specified multiple \
+                               times.")
+                        .takes_value(true)
+                        .multiple_occurrences(true)
+                        .value_name("start-end"),
+                )
+                .arg(
+                    Arg::new("contrast-with")
+                        .long("contrast-with")
+                        .help("Color that the generated colors need to contrast with, e.g. the \
+                               background they will be displayed on (see '--min-contrast'). \
+                               Unlike the global '--background' option, this does not affect \
+                               how colors are printed.")
+                        .takes_value(true)
+                        .value_name("color"),
+                )
+                .arg(
+                    Arg::new("min-contrast")
+                        .long("min-contrast")
+                        .help("Minimum WCAG contrast ratio between the generated colors and the \
+                               '--contrast-with' color [default: 3.0]")
+                        .takes_value(true)
+                        .requires("contrast-with")
+                        .value_name("ratio"),
+                )
                 .arg(
                     Arg::new("verbose")
                         .long("verbose")
@@ -207,22 +371,48 @@ pub fn build_cli() -> Command<'static> {
                   pastel random -n 20 | pastel format rgb")
                 .arg(
                     Arg::new("type")
-                        .help("Output format type. Note that the 'ansi-*-escapecode' formats print \
+                        .help("Output format type (see --help for a list of all types)")
+                        .long_help("Output format type. Note that the 'ansi-*-escapecode' formats print \
                                ansi escape sequences to the terminal that will not be visible \
-                               unless something else is printed in addition.")
-                        .possible_values(&["rgb", "rgb-float", "hex",
-                                           "hsl", "hsl-hue", "hsl-saturation", "hsl-lightness",
-                                           "hsv", "hsv-hue", "hsv-saturation", "hsv-value",
-                                           "hwb", "hwb-hue", "hwb-whiteness", "hwb-blackness",
-                                           "lch", "lch-lightness", "lch-chroma", "lch-hue",
-                                           "lab", "lab-a", "lab-b",
-                                           "luminance", "brightness",
-                                           "ansi-8bit", "ansi-24bit",
-                                           "ansi-8bit-escapecode", "ansi-24bit-escapecode",
-                                           "cmyk", "name"])
+                               unless something else is printed in addition.\n\n\
+                               Hex formats: 'hex' (#RRGGBB, or #RRGGBBAA for transparent colors), \
+                               'hex8' (always #RRGGBBAA), 'hex-short' (#RGB or #RGBA if possible), \
+                               'hex-argb' (0xAARRGGBB, e.g. for Android) and 'hex-rgba' (0xRRGGBBAA).\n\n\
+                               Code formats: 'uicolor' (UIKit), 'swiftui', 'compose' (Jetpack \
+                               Compose), 'android-xml' (color resource), 'css-var' (CSS custom \
+                               property), 'scss', 'less' (variables) and 'tailwind' (the 'colors' \
+                               object of a Tailwind config). The variable names are taken from the \
+                               closest named color, with an index suffix for repeated names \
+                               ('red', 'red-2', ...).")
+                        .value_parser(format_type_parser())
                         .ignore_case(true)
                         .default_value("hex")
                 )
+                .arg(
+                    Arg::new("template")
+                        .long("template")
+                        .short('t')
+                        .value_name("template")
+                        .takes_value(true)
+                        .multiple_values(true)
+                        .conflicts_with("type")
+                        .help("Format the colors with a custom template, e.g. '{r},{g},{b} /* {name} */'. \
+                               The colors are given after the template.")
+                        .long_help(
+                            "Format the colors with a custom template instead of a format type. \
+                             Placeholders in curly braces are replaced by the corresponding format \
+                             type (e.g. '{hex}', '{hsl-hue}', '{name}', '{luminance}') or color \
+                             channel: '{r}', '{g}', '{b}', '{alpha}', '{hsl.h}', '{hsl.s}', \
+                             '{hsl.l}', '{hsv.h}', '{hsv.s}', '{hsv.v}', '{hwb.h}', '{hwb.w}', \
+                             '{hwb.b}', '{lab.l}', '{lab.a}', '{lab.b}', '{lch.l}', '{lch.c}', \
+                             '{lch.h}'. Numbers can be formatted with a given precision, e.g. \
+                             '{lch.c:.1}'. Use '{{' and '}}' for literal braces.\n\n\
+                             The colors are given after the template (instead of a format type). \
+                             Without colors, they are read from standard input.\n\n\
+                             Example:\n  \
+                               pastel format --template '{r},{g},{b} /* {name} */' red blue",
+                        ),
+                )
                 .arg(color_arg.clone()),
         )
         .subcommand(
@@ -277,15 +467,35 @@ pub fn build_cli() -> Command<'static> {
                 .about("Generate an interpolating sequence of colors")
                 .long_about("Generate a sequence of colors that interpolates between the specified colors.\n\
                             The interpolation is performed in the specified color space.\n\n\
+                            Like in CSS gradients, a color stop can be placed at an explicit position by \
+                            appending '@' and a number between 0 and 1 or a percentage (e.g. 'red@0.3' or \
+                            'red@30%'). Stops without a position are spaced evenly between their neighbors. \
+                            A single '@position' argument between two color stops is a midpoint hint: it \
+                            moves the point where the halfway color appears.\n\n\
                             Example:\n  \
                               pastel gradient --colorspace=HSL ffffcc fd8d3c\n  \
-                              pastel gradient 555ee4 white d84341 -n 15")
+                              pastel gradient 555ee4 white d84341 -n 15\n  \
+                              pastel gradient red@0 blue@0.3 yellow@1\n  \
+                              pastel gradient black @20% white --easing ease-in-out\n  \
+                              pastel gradient black red yellow white --interpolation bspline\n  \
+                              pastel gradient --preset viridis -n 20\n  \
+                              pastel gradient --colorspace=LCh --hue longer red red -n 12")
                 .arg(
                     Arg::new("color")
                         .value_name("color")
-                        .help("Color stops in the color gradient")
+                        .help("Color stops (and midpoint hints) in the color gradient")
                         .multiple_occurrences(true)
-                        .required(true),
+                        .required_unless_present("preset"),
+                )
+                .arg(
+                    Arg::new("preset")
+                        .long("preset")
+                        .short('p')
+                        .help("Use the colors of a predefined colormap (see 'pastel colormap --help') \
+                               instead of color arguments")
+                        .takes_value(true)
+                        .conflicts_with("color")
+                        .value_name("colormap"),
                 )
                 .arg(
                     Arg::new("number")
@@ -299,6 +509,86 @@ pub fn build_cli() -> Command<'static> {
                 .arg(
                     colorspace_arg.clone()
                 )
+                .arg(
+                    hue_arg.clone()
+                )
+                .arg(
+                    no_premultiply_arg.clone()
+                )
+                .arg(
+                    Arg::new("easing")
+                        .long("easing")
+                        .short('e')
+                        .help("Easing function that is applied between each pair of color stops: \
+                               'linear', 'ease', 'ease-in', 'ease-out', 'ease-in-out', 'smoothstep' \
+                               or 'cubic-bezier(x1, y1, x2, y2)'")
+                        .takes_value(true)
+                        .default_value("linear")
+                        .value_name("function"),
+                )
+                .arg(
+                    Arg::new("interpolation")
+                        .long("interpolation")
+                        .short('i')
+                        .help(
+                            "Interpolation method:\n   \
+                             linear:       mix neighboring color stops in the given colorspace\n   \
+                             bspline:      smooth B-spline in Lab (passes through the first and last color)\n   \
+                             catmull-rom:  smooth curve in Lab through all colors (may overshoot)\n   \
+                             monotone:     smooth curve in Lab through all colors, without overshooting\n\
+                             \n\
+                             Default method: 'linear'\n ",
+                        )
+                        .possible_values(["linear", "bspline", "catmull-rom", "monotone"])
+                        .hide_default_value(true)
+                        .hide_possible_values(true)
+                        .default_value("linear")
+                        .value_name("method"),
+                )
+                .arg(
+                    Arg::new("correct-lightness")
+                        .long("correct-lightness")
+                        .help("Re-distribute the colors such that the (Lab) lightness changes linearly \
+                               along the gradient. This results in perceptually more uniform steps for \
+                               sequential color scales, e.g. for heatmaps."),
+                )
+        )
+        .subcommand(
+            Command::new("colormap")
+                .about("Print the colors of a predefined colormap")
+                .long_about("Print the colors of a predefined colormap for data visualization.\n\n\
+                            Sequential and diverging colormaps are sampled at the given number of evenly \
+                            spaced positions, exactly like 'pastel gradient --preset'. For qualitative colormaps, the first N colors are printed \
+                            (all of them by default).\n\n\
+                            Available colormaps:\n  \
+                              sequential:   viridis, inferno, magma, plasma, cividis, turbo, Blues, BuGn,\n                \
+                                            BuPu, GnBu, Greens, Greys, Oranges, OrRd, PuBu, PuBuGn, PuRd,\n                \
+                                            Purples, RdPu, Reds, YlGn, YlGnBu, YlOrBr, YlOrRd\n  \
+                              diverging:    BrBG, PiYG, PRGn, PuOr, RdBu, RdGy, RdYlBu, RdYlGn, Spectral\n  \
+                              qualitative:  Accent, Dark2, Paired, Pastel1, Pastel2, Set1, Set2, Set3\n\n\
+                            Example:\n  \
+                              pastel colormap viridis -n 5\n  \
+                              pastel colormap Set2")
+                .arg(
+                    Arg::new("name")
+                        .value_name("name")
+                        .help("Name of the colormap (case-insensitive)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("number")
+                        .long("number")
+                        .short('n')
+                        .help("Number of colors to print")
+                        .takes_value(true)
+                        .value_name("count"),
+                )
+                .arg(
+                    colorspace_arg.clone()
+                )
+                .arg(
+                    hue_arg.clone()
+                )
         )
         .subcommand(
             Command::new("mix")
@@ -310,6 +600,12 @@ pub fn build_cli() -> Command<'static> {
                 .arg(
                     colorspace_arg.clone()
                 )
+                .arg(
+                    hue_arg.clone()
+                )
+                .arg(
+                    no_premultiply_arg.clone()
+                )
                 .arg(
                     Arg::new("fraction")
                         .long("fraction")
@@ -327,6 +623,58 @@ pub fn build_cli() -> Command<'static> {
                 )
                 .arg(color_arg.clone()),
         )
+        .subcommand(
+            Command::new("blend")
+                .about("Blend colors onto a backdrop color using a blend mode")
+                .long_about(
+                    "Blend the given (source) colors onto a backdrop color using one of the blend \
+                     modes of the W3C Compositing specification, and composite the result over the \
+                     backdrop. This reproduces the layer blend modes of graphics editors.\n\n\
+                     Available blend modes:\n  \
+                       normal, multiply, screen, overlay, darken, lighten, color-dodge, color-burn,\n  \
+                       hard-light, soft-light, difference, exclusion, hue, saturation, color,\n  \
+                       luminosity\n\n\
+                     Example:\n  \
+                       pastel blend multiply lightblue orange\n  \
+                       pastel blend overlay gray 'rgba(255, 0, 0, 0.5)'")
+                .arg(
+                    Arg::new("mode")
+                        .value_name("mode")
+                        .help("The blend mode")
+                        .possible_values([
+                            "normal", "multiply", "screen", "overlay", "darken", "lighten",
+                            "color-dodge", "color-burn", "hard-light", "soft-light", "difference",
+                            "exclusion", "hue", "saturation", "color", "luminosity",
+                        ])
+                        .hide_possible_values(true)
+                        .ignore_case(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("backdrop")
+                        .value_name("backdrop")
+                        .help("The backdrop color onto which the other colors are blended")
+                        .required(true),
+                )
+                .arg(color_arg.clone()),
+        )
+        .subcommand(
+            Command::new("flatten")
+                .about("Composite transparent colors onto an opaque background")
+                .long_about(
+                    "Remove the transparency of a color by compositing it onto a background color \
+                     (source-over). The background defaults to the global '--background' color, \
+                     or white if none is given.\n\n\
+                     Example:\n  \
+                       pastel flatten --on black 'rgba(255, 0, 0, 0.5)'")
+                .arg(
+                    Arg::new("on")
+                        .long("on")
+                        .value_name("color")
+                        .help("The background color"),
+                )
+                .arg(color_arg.clone()),
+        )
         .subcommand(
             Command::new("colorblind")
                 .about("Simulate a color under a certain colorblindness profile")
@@ -339,7 +687,7 @@ pub fn build_cli() -> Command<'static> {
                     Arg::new("type")
                         .help("The type of colorblindness that should be simulated (protanopia, \
                                deuteranopia, tritanopia)")
-                        .possible_values(&["prot", "deuter", "trit"])
+                        .possible_values(["prot", "deuter", "trit"])
                         .ignore_case(true)
                         .required(true),
                 )
@@ -354,11 +702,12 @@ pub fn build_cli() -> Command<'static> {
                 .arg(
                     Arg::new("property")
                         .help("The property that should be changed")
-                        .possible_values(&["lightness", "hue", "chroma",
+                        .possible_values(["lightness", "hue", "chroma",
                                            "lab-a", "lab-b",
                                            "red", "green", "blue",
                                            "hsl-hue", "hsl-saturation", "hsl-lightness",
-                                           "hwb-hue", "hwb-whiteness", "hwb-blackness"])
+                                           "hwb-hue", "hwb-whiteness", "hwb-blackness",
+                                           "alpha"])
                         .ignore_case(true)
                         .required(true),
                 )
@@ -479,6 +828,136 @@ pub fn build_cli() -> Command<'static> {
                             i.e. to create a background color for a given text color.")
                 .arg(color_arg.clone()),
         )
+        .subcommand(
+            Command::new("tokens")
+                .about("Transform the colors in a design tokens file")
+                .long_about(
+                    "Read a design tokens file in the W3C Design Tokens Community Group (DTCG) or \
+                     Style Dictionary JSON format, transform all color tokens and write the \
+                     resulting tokens file to standard output. The structure, names and all other \
+                     tokens are kept. References to other tokens ('{color.brand.500}') are \
+                     resolved when needed and kept as references in the output, unless \
+                     '--resolve-aliases' is given.\n\n\
+                     Example:\n  \
+                       pastel tokens tokens.json --exec 'lighten 0.1 | saturate 0.1' --to rgb\n  \
+                       pastel tokens style-dictionary.json --output-format dtcg")
+                .arg(
+                    Arg::new("file")
+                        .help("The tokens file. If no file or '-' is given, the tokens are read \
+                               from standard input."),
+                )
+                .arg(
+                    Arg::new("exec")
+                        .long("exec")
+                        .short('e')
+                        .value_name("commands")
+                        .help("The pastel commands that are applied to each color, separated by '|' \
+                               (e.g. 'darken 0.1 | rotate 10')"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("type")
+                        .help("The output format type of the colors (see 'pastel format --help')")
+                        .default_value("hex"),
+                )
+                .arg(
+                    Arg::new("output-format")
+                        .long("output-format")
+                        .value_name("format")
+                        .help("The format of the output file (dtcg, style-dictionary). By default, \
+                               the format of the input file is used.")
+                        .possible_values(["dtcg", "style-dictionary"])
+                        .hide_possible_values(true)
+                        .ignore_case(true),
+                )
+                .arg(
+                    Arg::new("resolve-aliases")
+                        .long("resolve-aliases")
+                        .help("Replace references to other color tokens by their (transformed) values"),
+                ),
+        )
+        .subcommand(
+            Command::new("replace")
+                .about("Transform the colors in text files (CSS, SCSS, source code, …)")
+                .long_about(
+                    "Find all color literals (hex colors, functional notations like 'rgb(…)' or \
+                     'hsl(…)', and named colors with '--names') in the given files or standard \
+                     input, transform \
+                     them with the given pastel commands and write the text with the replaced \
+                     colors to standard output (or back to the files with '--in-place'). The \
+                     notation of each color literal is kept, unless '--to' is given.\n\n\
+                     Example:\n  \
+                       pastel replace --exec 'darken 0.1' --in-place style.css\n  \
+                       pastel replace --to hex < theme.scss")
+                .arg(
+                    Arg::new("file")
+                        .help("The files to process. If no file is given, the text is read from \
+                               standard input.")
+                        .multiple_values(true),
+                )
+                .arg(
+                    Arg::new("exec")
+                        .long("exec")
+                        .short('e')
+                        .value_name("commands")
+                        .help("The pastel commands that are applied to each color, separated by '|' \
+                               (e.g. 'darken 0.1 | rotate 10')"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("type")
+                        .help("Write all colors in the given format type (see 'pastel format \
+                               --help') instead of keeping the original notation"),
+                )
+                .arg(
+                    Arg::new("in-place")
+                        .long("in-place")
+                        .short('i')
+                        .requires("file")
+                        .help("Write the results back to the files"),
+                )
+                .arg(
+                    Arg::new("names")
+                        .long("names")
+                        .help("Also replace named colors like 'red'. Note that this also \
+                               replaces color names in ordinary words and identifiers."),
+                ),
+        )
+        .subcommand(
+            Command::new("grep")
+                .about("Find and report color literals in files")
+                .long_about(
+                    "Search the given files and directories (recursively) for color literals and \
+                     print the position (file:line:column), the literal, its hex value and the \
+                     nearest named color ('~' marks approximate matches) of each of them. \
+                     Afterwards, groups of near-duplicate colors are listed, i.e. different colors \
+                     with a perceptual distance (CIEDE2000) below the given threshold.\n\n\
+                     Example:\n  \
+                       pastel grep src/styles\n  \
+                       pastel grep --threshold 5 theme.css")
+                .arg(
+                    Arg::new("path")
+                        .help("The files or directories to search. If no path is given, the text \
+                               is read from standard input.")
+                        .multiple_values(true),
+                )
+                .arg(
+                    Arg::new("threshold")
+                        .long("threshold")
+                        .short('t')
+                        .value_name("ΔE")
+                        .help("Colors with a distance below this threshold are reported as \
+                               near-duplicates. Use 0 to disable the report.")
+                        .default_value("3"),
+                )
+                .arg(
+                    Arg::new("names")
+                        .long("names")
+                        .help("Also report named colors like 'red'"),
+                ),
+        )
         .subcommand(
             Command::new("colorcheck")
                 .about("Check if your terminal emulator supports 24-bit colors."),
@@ -489,7 +968,7 @@ pub fn build_cli() -> Command<'static> {
                 .short('m')
                 .value_name("mode")
                 .help("Specify the terminal color mode: 24bit, 8bit, off, *auto*")
-                .possible_values(&["24bit", "8bit", "off", "auto"])
+                .possible_values(["24bit", "8bit", "off", "auto"])
                 .default_value(if output_vt100::try_init().is_ok() {"auto"} else {"off"})
                 .hide_possible_values(true)
                 .hide_default_value(true)
@@ -508,6 +987,92 @@ pub fn build_cli() -> Command<'static> {
                 .ignore_case(true)
                 .help("Use a specific tool to pick the colors")
         )
+        .arg(
+            Arg::new("background")
+                .long("background")
+                .value_name("color")
+                .help("Background color for transparent colors")
+                .long_help(
+                    "The background color that transparent colors are composited onto when they \
+                     are printed in a format that can not represent transparency (hex, ANSI, CMYK, \
+                     name) or shown in the terminal. Also used as the default for \
+                     'pastel flatten --on'. Use '-' to read the color from standard input or \
+                     'pick' to use a color picker.",
+                )
+        )
+        .arg(
+            Arg::new("on-error")
+                .long("on-error")
+                .value_name("policy")
+                .help("What to do with colors on standard input that can not be parsed: *fail*, \
+                       skip, warn, passthrough")
+                .long_help(
+                    "What to do with colors on standard input that can not be parsed:\n  \
+                     - fail: stop with an error (default)\n  \
+                     - skip: ignore the invalid color\n  \
+                     - warn: ignore the invalid color and print a warning (with the line and \
+                     column number) to standard error\n  \
+                     - passthrough: copy the whole line to the output unchanged (this also \
+                     applies to blank lines and comments)\n\n\
+                     A line can contain several colors, separated by whitespace, commas or \
+                     semicolons. With 'skip' and 'warn', the valid colors of a line are kept. \
+                     Blank lines and comments ('# …' or '// …') are ignored. If any color could \
+                     not be parsed, pastel exits with status 3.",
+                )
+                .possible_values(["fail", "skip", "warn", "passthrough"])
+                .default_value("fail")
+                .hide_possible_values(true)
+                .hide_default_value(true)
+        )
+        .arg(
+            Arg::new("field")
+                .long("field")
+                .value_name("N")
+                .help("Read the colors on standard input from the N-th field of each line")
+                .long_help(
+                    "Read the colors on standard input from the N-th field (starting at 1) of \
+                     each line. The color in this field is replaced by the output of the command, \
+                     all other fields are passed through unchanged. Fields are separated by \
+                     whitespace, unless a '--delimiter' is given. Delimiters within double \
+                     quotes or parentheses are ignored.\n\n\
+                     A header line (as in CSV files) can not be parsed as a color. Use \
+                     '--on-error passthrough' to copy it to the output unchanged.\n\n\
+                     Example:\n\n  \
+                       printf 'primary,#ff0000,main\\n' | pastel --field 2 --delimiter , lighten 0.1\n  \
+                       pastel --field 2 --delimiter , --on-error passthrough format hex < colors.csv",
+                )
+                .conflicts_with("json-path")
+        )
+        .arg(
+            Arg::new("delimiter")
+                .long("delimiter")
+                .value_name("delim")
+                .help("The field delimiter for '--field' (use '\\t' for tabs) [default: whitespace]")
+                .forbid_empty_values(true)
+                .requires("field")
+        )
+        .arg(
+            Arg::new("jobs")
+                .long("jobs")
+                .short('j')
+                .value_name("N")
+                .help("Number of threads that process colors from standard input, 0 for one \
+                       thread per CPU core [default: 1]. The order of the output is preserved.")
+        )
+        .arg(
+            Arg::new("json-path")
+                .long("json-path")
+                .value_name("path")
+                .help("Read the colors on standard input from a JSON value in each line")
+                .long_help(
+                    "Read the colors on standard input from a string value in each line, where \
+                     every line is a JSON document (JSON Lines). The path consists of \
+                     dot-separated object keys and array indices, as in 'theme.colors.0' or \
+                     'theme.colors[0]'. A path that starts with '/' is a JSON pointer, as in \
+                     '/theme/color.primary' (for keys that contain dots). The value is replaced \
+                     by the output of the command, the rest of the document is passed through.",
+                )
+        )
 }
 
 #[test]
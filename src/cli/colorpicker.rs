@@ -86,7 +86,7 @@ pub fn run_external_colorpicker(picker: Option<&str>) -> Result<String> {
             // Check if tool requires some post processing of the output
             if let Some(post_process) = tool.post_process {
                 return post_process(color)
-                    .map_err(|error| PastelError::ColorParseError(error.to_string()));
+                    .map_err(|error| PastelError::ColorParseError(error.to_string(), None));
             } else {
                 return Ok(color);
             }
@@ -1,15 +1,39 @@
+use pastel::colorspace::ColorSpace;
 use pastel::Color;
-use pastel::{Fraction, LCh, Lab, HSLA, HWBA, RGBA};
+use pastel::{Fraction, HueInterpolation, LCh, Lab, LinearRGBA, HSLA, HWBA, RGBA};
+
+pub type MixingFunction = Box<dyn Fn(&Color, &Color, Fraction) -> Color>;
+
+fn mixing_function<C: ColorSpace>(hue: HueInterpolation, premultiplied: bool) -> MixingFunction {
+    if premultiplied {
+        Box::new(move |c1: &Color, c2: &Color, f: Fraction| c1.mix_premultiplied::<C>(c2, f, hue))
+    } else {
+        Box::new(move |c1: &Color, c2: &Color, f: Fraction| c1.mix_with_hue::<C>(c2, f, hue))
+    }
+}
 
 pub fn get_mixing_function(
     colorspace_name: &str,
-) -> Box<dyn Fn(&Color, &Color, Fraction) -> Color> {
+    hue: HueInterpolation,
+    premultiplied: bool,
+) -> MixingFunction {
     match colorspace_name.to_lowercase().as_ref() {
-        "rgb" => Box::new(|c1: &Color, c2: &Color, f: Fraction| c1.mix::<RGBA<f64>>(c2, f)),
-        "hsl" => Box::new(|c1: &Color, c2: &Color, f: Fraction| c1.mix::<HSLA>(c2, f)),
-        "hwb" => Box::new(|c1: &Color, c2: &Color, f: Fraction| c1.mix::<HWBA>(c2, f)),
-        "lab" => Box::new(|c1: &Color, c2: &Color, f: Fraction| c1.mix::<Lab>(c2, f)),
-        "lch" => Box::new(|c1: &Color, c2: &Color, f: Fraction| c1.mix::<LCh>(c2, f)),
+        "rgb" => mixing_function::<RGBA<f64>>(hue, premultiplied),
+        "srgb-linear" => mixing_function::<LinearRGBA>(hue, premultiplied),
+        "hsl" => mixing_function::<HSLA>(hue, premultiplied),
+        "hwb" => mixing_function::<HWBA>(hue, premultiplied),
+        "lab" => mixing_function::<Lab>(hue, premultiplied),
+        "lch" => mixing_function::<LCh>(hue, premultiplied),
         _ => unreachable!("Unknown color space"),
     }
 }
+
+pub fn get_hue_interpolation(method: &str) -> HueInterpolation {
+    match method {
+        "shorter" => HueInterpolation::Shorter,
+        "longer" => HueInterpolation::Longer,
+        "increasing" => HueInterpolation::Increasing,
+        "decreasing" => HueInterpolation::Decreasing,
+        _ => unreachable!("Unknown hue interpolation method"),
+    }
+}
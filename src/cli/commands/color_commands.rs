@@ -1,6 +1,7 @@
-use crate::colorspace::get_mixing_function;
+use crate::colorspace::{get_hue_interpolation, get_mixing_function};
 use crate::commands::prelude::*;
 
+use pastel::blend::{self, BlendMode};
 use pastel::ColorblindnessType;
 use pastel::Fraction;
 
@@ -72,11 +73,42 @@ color_command!(MixCommand, config, matches, color, {
     )?;
     let fraction = Fraction::from(1.0 - number_arg(matches, "fraction")?);
 
-    let mix = get_mixing_function(matches.value_of("colorspace").expect("required argument"));
+    let mix = get_mixing_function(
+        matches.value_of("colorspace").expect("required argument"),
+        get_hue_interpolation(matches.value_of("hue").expect("required argument")),
+        !matches.is_present("no-premultiply"),
+    );
 
     mix(&base, color, fraction)
 });
 
+color_command!(BlendCommand, config, matches, color, {
+    let mode = matches
+        .value_of("mode")
+        .expect("required argument")
+        .parse::<BlendMode>()
+        .expect("valid blend mode");
+
+    let backdrop = ColorArgIterator::from_color_arg(
+        config,
+        matches.value_of("backdrop").expect("required argument"),
+        &mut PrintSpectrum::Yes,
+    )?;
+
+    blend::blend(&backdrop, color, mode)
+});
+
+color_command!(FlattenCommand, config, matches, color, {
+    let background = match matches.value_of("on") {
+        Some(background) => {
+            ColorArgIterator::from_color_arg(config, background, &mut PrintSpectrum::Yes)?
+        }
+        None => config.background.clone().unwrap_or_else(Color::white),
+    };
+
+    background.composite(color)
+});
+
 color_command!(ColorblindCommand, config, matches, color, {
     // The type of colorblindness selected (protanopia, deuteranopia, tritanopia)
     let cb_ty = matches.value_of("type").expect("required argument");
@@ -168,6 +200,7 @@ color_command!(SetCommand, config, matches, color, {
             }
             Color::from_lab(lab.l, lab.a, lab.b, lab.alpha)
         }
+        "alpha" => color.with_alpha(clamp(0.0, 1.0, value)),
         "hue" | "chroma" => {
             let mut lch = color.to_lch();
             match property {
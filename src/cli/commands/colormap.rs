@@ -0,0 +1,49 @@
+use crate::colorspace::{get_hue_interpolation, get_mixing_function};
+use crate::commands::gradient::sample_color_scale;
+use crate::commands::prelude::*;
+
+use pastel::colormaps::{colormap_by_name, ColormapKind};
+
+pub struct ColormapCommand;
+
+impl GenericCommand for ColormapCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let name = matches.value_of("name").expect("required argument");
+        let colormap =
+            colormap_by_name(name).ok_or_else(|| PastelError::UnknownColormap(name.into()))?;
+
+        let count = matches
+            .value_of("number")
+            .map(|count| {
+                count
+                    .parse::<usize>()
+                    .map_err(|_| PastelError::CouldNotParseNumber(count.into()))
+            })
+            .transpose()?;
+
+        let colors = if colormap.kind == ColormapKind::Qualitative {
+            let colors = colormap.colors();
+            let count = count.unwrap_or(colors.len());
+            if count > colors.len() {
+                return Err(PastelError::ColormapNotEnoughColors(
+                    colormap.name.into(),
+                    colors.len(),
+                ));
+            }
+            colors.into_iter().take(count).collect()
+        } else {
+            let mix = get_mixing_function(
+                matches.value_of("colorspace").expect("required argument"),
+                get_hue_interpolation(matches.value_of("hue").expect("required argument")),
+                true,
+            );
+            sample_color_scale(&colormap.color_scale(), count.unwrap_or(10), None, &mix)?
+        };
+
+        for color in colors {
+            out.show_color(config, &color)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
+use std::fs;
 use std::io::{self, Write};
 
 use crate::commands::prelude::*;
 
 use pastel::ansi::Stream;
-use pastel::distinct::{self, DistanceMetric, IterationStatistics};
+use pastel::distinct::{self, Algorithm, ColorConstraints, DistanceMetric, IterationStatistics};
+use pastel::parser::try_parse_color;
 use pastel::{Fraction, HSLA};
 
 pub struct DistinctCommand;
@@ -71,7 +73,7 @@ fn print_distance_matrix(
         DistanceMetric::CIEDE2000 => c1.distance_delta_e_ciede2000(c2),
     };
 
-    let mut min = std::f64::MAX;
+    let mut min = f64::MAX;
     let mut max = 0.0;
     for i in 0..count {
         for j in 0..count {
@@ -125,56 +127,185 @@ fn print_distance_matrix(
     Ok(())
 }
 
+fn optional_number_arg(matches: &ArgMatches, name: &str) -> Result<Option<f64>> {
+    matches
+        .value_of(name)
+        .map(|value_str| {
+            value_str
+                .parse::<f64>()
+                .map_err(|_| PastelError::CouldNotParseNumber(value_str.into()))
+        })
+        .transpose()
+}
+
+fn parse_hue_range(range: &str) -> Result<(f64, f64)> {
+    let error = || PastelError::CouldNotParseHueRange(range.into());
+
+    let (start, end) = range.split_once('-').ok_or_else(error)?;
+    let start = start.trim().parse::<f64>().map_err(|_| error())?;
+    let end = end.trim().parse::<f64>().map_err(|_| error())?;
+
+    Ok((start, end))
+}
+
+fn constraints_from_args(matches: &ArgMatches, config: &Config) -> Result<ColorConstraints> {
+    let mut constraints = ColorConstraints::default();
+
+    if let Some(l) = optional_number_arg(matches, "min-lightness")? {
+        constraints.min_lightness = l;
+    }
+    if let Some(l) = optional_number_arg(matches, "max-lightness")? {
+        constraints.max_lightness = l;
+    }
+    if let Some(c) = optional_number_arg(matches, "min-chroma")? {
+        constraints.min_chroma = c;
+    }
+    if let Some(c) = optional_number_arg(matches, "max-chroma")? {
+        constraints.max_chroma = c;
+    }
+
+    if let Some(ranges) = matches.values_of("exclude-hue") {
+        constraints.excluded_hues = ranges.map(parse_hue_range).collect::<Result<_>>()?;
+    }
+
+    if let Some(background) = matches.value_of("contrast-with") {
+        let background =
+            ColorArgIterator::from_color_arg(config, background, &mut PrintSpectrum::Yes)?;
+        let min_contrast = optional_number_arg(matches, "min-contrast")?.unwrap_or(3.0);
+        constraints.background = Some((background, min_contrast));
+    }
+
+    Ok(constraints)
+}
+
+/// Read the palette that should be extended (`--extend`) from the `--colors` arguments, from a
+/// file, or from standard input.
+fn existing_palette(matches: &ArgMatches, config: &Config) -> Result<Vec<Color>> {
+    if matches.occurrences_of("number") > 0 || matches.is_present("color") {
+        return Err(PastelError::DistinctColorExtendWithPositionalArguments);
+    }
+
+    if let Some(path) = matches.value_of("palette") {
+        return fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                try_parse_color(line)
+                    .map_err(|err| PastelError::ColorParseError(line.into(), Some(err)))
+            })
+            .collect();
+    }
+
+    ColorArgIterator::from_args(config, matches.values_of("colors"))?.collect()
+}
+
 impl GenericCommand for DistinctCommand {
     fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
         let stderr = io::stderr();
-        let mut stderr_lock = stderr.lock();
         let brush_stderr = Brush::from_environment(Stream::Stderr)?;
         let verbose_output = matches.is_present("verbose");
 
-        let count = matches.value_of("number").expect("required argument");
-        let count = count
-            .parse::<usize>()
-            .map_err(|_| PastelError::CouldNotParseNumber(count.into()))?;
-
-        if count < 2 {
-            return Err(PastelError::DistinctColorCountMustBeLargerThanOne);
-        }
-
         let distance_metric = match matches.value_of("metric").expect("required argument") {
             "CIE76" => DistanceMetric::CIE76,
             "CIEDE2000" => DistanceMetric::CIEDE2000,
             _ => unreachable!("Unknown distance metric"),
         };
 
-        let fixed_colors = match matches.values_of("color") {
-            None => vec![],
-            Some(positionals) => {
-                ColorArgIterator::FromPositionalArguments(config, positionals, PrintSpectrum::Yes)
-                    .collect::<Result<Vec<_>>>()?
+        let extend = matches.is_present("extend");
+
+        let (count, fixed_colors) = if extend {
+            let extend_count = matches.value_of("extend").expect("required argument");
+            let extend_count = extend_count
+                .parse::<usize>()
+                .map_err(|_| PastelError::CouldNotParseNumber(extend_count.into()))?;
+
+            if extend_count == 0 {
+                return Err(PastelError::DistinctColorExtendCountMustBePositive);
             }
+
+            let palette = existing_palette(matches, config)?;
+            (palette.len() + extend_count, palette)
+        } else {
+            let count = matches.value_of("number").expect("required argument");
+            let count = count
+                .parse::<usize>()
+                .map_err(|_| PastelError::CouldNotParseNumber(count.into()))?;
+
+            let fixed_colors = match matches.values_of("color") {
+                None => vec![],
+                Some(positionals) => ColorArgIterator::FromPositionalArguments(
+                    config,
+                    positionals,
+                    PrintSpectrum::Yes,
+                )
+                .collect::<Result<Vec<_>>>()?,
+            };
+
+            (count, fixed_colors)
         };
 
+        if count < 2 {
+            return Err(PastelError::DistinctColorCountMustBeLargerThanOne);
+        }
+
         let num_fixed_colors = fixed_colors.len();
         if num_fixed_colors > count {
             return Err(PastelError::DistinctColorFixedColorsCannotBeMoreThanCount);
         }
 
-        let mut callback: Box<dyn FnMut(&IterationStatistics)> = if verbose_output {
+        let constraints = constraints_from_args(matches, config)?;
+
+        let num_restarts = match matches.value_of("restarts") {
+            Some(restarts) => restarts
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| PastelError::CouldNotParseNumber(restarts.into()))?,
+            None => 1,
+        };
+
+        let callback: Box<dyn Fn(&IterationStatistics) + Sync> = if verbose_output {
             Box::new(|stats: &IterationStatistics| {
-                print_iteration(&mut stderr_lock, brush_stderr, stats).ok();
+                print_iteration(&mut io::stderr().lock(), brush_stderr, stats).ok();
             })
         } else {
             Box::new(|_: &IterationStatistics| {})
         };
 
-        let (mut colors, distance_result) =
-            distinct::distinct_colors(count, distance_metric, fixed_colors, callback.as_mut());
+        let algorithm = match matches.value_of("algorithm").expect("required argument") {
+            "annealing" => Algorithm::Annealing,
+            "greedy" => Algorithm::Greedy,
+            "hybrid" => Algorithm::Hybrid,
+            _ => unreachable!("Unknown algorithm"),
+        };
+
+        let (mut colors, distance_result) = distinct::distinct_colors_with(
+            algorithm,
+            count,
+            distance_metric,
+            fixed_colors,
+            constraints,
+            num_restarts,
+            callback.as_ref(),
+        )
+        .map_err(|_| PastelError::DistinctColorConstraintsUnsatisfiable)?;
 
         if matches.is_present("print-minimal-distance") {
             writeln!(out.handle, "{:.3}", distance_result.min_closest_distance)?;
         } else {
-            distinct::rearrange_sequence(&mut colors, distance_metric);
+            if extend {
+                // keep the order of the existing palette and append the new colors
+                distinct::rearrange_sequence(&mut colors[num_fixed_colors..], distance_metric);
+
+                writeln!(
+                    stderr.lock(),
+                    "Minimal distance to the new colors: {:.3}",
+                    distance_result.min_closest_distance
+                )?;
+            } else {
+                distinct::rearrange_sequence(&mut colors, distance_metric);
+            }
 
             if verbose_output {
                 print_distance_matrix(&mut stderr.lock(), brush_stderr, &colors, distance_metric)?;
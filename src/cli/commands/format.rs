@@ -1,72 +1,46 @@
 use crate::commands::prelude::*;
-use crate::utility::similar_colors;
-
-use pastel::ansi::Mode;
-use pastel::Format;
+use crate::formats::{channel_by_name, Channel, Template, VariableNames};
 
 pub struct FormatCommand;
 
-impl ColorCommand for FormatCommand {
-    fn run(
+enum Formatter {
+    Channel(&'static Channel),
+    Template(Template),
+}
+
+impl FormatCommand {
+    fn format(
         &self,
         out: &mut Output,
-        matches: &ArgMatches,
+        formatter: &Formatter,
         config: &Config,
+        names: &VariableNames,
         color: &Color,
     ) -> Result<()> {
-        let format_type = matches.value_of("type").expect("required argument");
-        let format_type = format_type.to_lowercase();
+        let background = config.background.as_ref();
 
-        let replace_escape = |code: &str| code.replace('\x1b', "\\x1b");
-
-        let output = match format_type.as_ref() {
-            "rgb" => color.to_rgb_string(Format::Spaces),
-            "rgb-float" => color.to_rgb_float_string(Format::Spaces),
-            "hex" => color.to_rgb_hex_string(true),
-            "hsl" => color.to_hsl_string(Format::Spaces),
-            "hsl-hue" => format!("{:.0}", color.to_hsla().h),
-            "hsl-saturation" => format!("{:.4}", color.to_hsla().s),
-            "hsl-lightness" => format!("{:.4}", color.to_hsla().l),
-            "hsv" => color.to_hsv_string(Format::Spaces),
-            "hsv-hue" => format!("{:.0}", color.to_hsva().h),
-            "hsv-saturation" => format!("{:.4}", color.to_hsva().s),
-            "hsv-value" => format!("{:.4}", color.to_hsva().v),
-            "hwb" => color.to_hwb_string(Format::Spaces),
-            "hwb-hue" => format!("{:.0}", color.to_hwba().h),
-            "hwb-whiteness" => format!("{:.4}", color.to_hwba().w),
-            "hwb-blackness" => format!("{:.4}", color.to_hwba().b),
-            "lch" => color.to_lch_string(Format::Spaces),
-            "lch-lightness" => format!("{:.2}", color.to_lch().l),
-            "lch-chroma" => format!("{:.2}", color.to_lch().c),
-            "lch-hue" => format!("{:.2}", color.to_lch().h),
-            "lab" => color.to_lab_string(Format::Spaces),
-            "lab-a" => format!("{:.2}", color.to_lab().a),
-            "lab-b" => format!("{:.2}", color.to_lab().b),
-            "luminance" => format!("{:.3}", color.luminance()),
-            "brightness" => format!("{:.3}", color.brightness()),
-            "ansi-8bit" => replace_escape(&color.to_ansi_sequence(Mode::Ansi8Bit)),
-            "ansi-24bit" => replace_escape(&color.to_ansi_sequence(Mode::TrueColor)),
-            "ansi-8bit-escapecode" => color.to_ansi_sequence(Mode::Ansi8Bit),
-            "ansi-24bit-escapecode" => color.to_ansi_sequence(Mode::TrueColor),
-            "cmyk" => color.to_cmyk_string(Format::Spaces),
-            "name" => similar_colors(color)[0].name.to_owned(),
-            &_ => {
-                unreachable!("Unknown format type");
-            }
+        let output = match formatter {
+            Formatter::Channel(channel) => channel.format_in_palette(color, background, names),
+            Formatter::Template(template) => template.render(color, background),
         };
 
         let write_colored_line = !matches!(
-            format_type.as_ref(),
-            "ansi-8bit-escapecode" | "ansi-24bit-escapecode"
+            formatter,
+            Formatter::Channel(channel)
+                if matches!(channel.name(), "ansi-8bit-escapecode" | "ansi-24bit-escapecode")
         );
 
         if write_colored_line {
+            let color = match background {
+                Some(background) => background.composite(color),
+                None => color.clone(),
+            };
             writeln!(
                 out.handle,
                 "{}",
                 config
                     .brush
-                    .paint(output, color.text_color().ansi_style().on(color))
+                    .paint(output, color.text_color().ansi_style().on(&color))
             )?;
         } else {
             write!(out.handle, "{}", output)?;
@@ -75,3 +49,50 @@ impl ColorCommand for FormatCommand {
         Ok(())
     }
 }
+
+impl GenericCommand for FormatCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let (formatter, colors) = match matches.values_of("template") {
+            Some(mut values) => {
+                // The template is followed by the colors
+                let template = values.next().expect("required argument");
+                let formatter = Formatter::Template(Template::parse(template)?);
+
+                let colors = if matches.values_of("template").map_or(0, Iterator::count) > 1 {
+                    Some(values)
+                } else {
+                    None
+                };
+                (formatter, colors)
+            }
+            None => {
+                let format_type = matches.value_of("type").expect("required argument");
+                let formatter = Formatter::Channel(
+                    channel_by_name(format_type)
+                        .ok_or_else(|| PastelError::UnknownFormatType(format_type.into()))?,
+                );
+                (formatter, matches.values_of("color"))
+            }
+        };
+
+        let enclosing = match formatter {
+            Formatter::Channel(channel) => channel.enclosing(),
+            Formatter::Template(_) => None,
+        };
+
+        if let Some((start, _)) = enclosing {
+            writeln!(out.handle, "{}", start)?;
+        }
+
+        let names = VariableNames::default();
+        for_each_color(config, colors, out, |out, color| {
+            self.format(out, &formatter, config, &names, color)
+        })?;
+
+        if let Some((_, end)) = enclosing {
+            writeln!(out.handle, "{}", end)?;
+        }
+
+        Ok(())
+    }
+}
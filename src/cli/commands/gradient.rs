@@ -1,48 +1,241 @@
-use crate::colorspace::get_mixing_function;
+use crate::colorspace::{get_hue_interpolation, get_mixing_function, MixingFunction};
 use crate::commands::prelude::*;
 
+use pastel::colormaps::colormap_by_name;
 use pastel::ColorScale;
+use pastel::Easing;
 use pastel::Fraction;
+use pastel::Spline;
 
 pub struct GradientCommand;
 
-impl GenericCommand for GradientCommand {
-    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
-        let count = matches.value_of("number").expect("required argument");
-        let count = count
-            .parse::<usize>()
-            .map_err(|_| PastelError::CouldNotParseNumber(count.into()))?;
-        if count < 2 {
-            return Err(PastelError::GradientNumberMustBeLargerThanOne);
+/// A color stop with an optional position, or a midpoint hint between two color stops.
+enum GradientArg<'a> {
+    Stop(&'a str, Option<f64>),
+    Hint(&'a str, f64),
+}
+
+/// Parse a position like `0.3` or `30%`.
+fn parse_position(position: &str) -> Result<f64> {
+    let value = match position.strip_suffix('%') {
+        Some(percentage) => percentage.trim().parse::<f64>().map(|p| p / 100.0),
+        None => position.trim().parse::<f64>(),
+    };
+
+    value
+        .ok()
+        .filter(|v| (0.0..=1.0).contains(v))
+        .ok_or_else(|| PastelError::CouldNotParseStopPosition(position.into()))
+}
+
+fn parse_gradient_arg(arg: &str) -> Result<GradientArg<'_>> {
+    match arg.rsplit_once('@') {
+        Some(("", position)) => Ok(GradientArg::Hint(arg, parse_position(position)?)),
+        Some((color, position)) => Ok(GradientArg::Stop(color, Some(parse_position(position)?))),
+        None => Ok(GradientArg::Stop(arg, None)),
+    }
+}
+
+/// Fill in missing color stop positions like CSS does: the first and last stop default to 0 and
+/// 1, a position smaller than a previous one is raised to that value, and runs of stops without
+/// a position are spaced evenly between their neighbors.
+fn resolve_positions(positions: &[Option<f64>]) -> Vec<f64> {
+    let count = positions.len();
+
+    let mut fixed: Vec<Option<f64>> = positions.to_vec();
+    if fixed[0].is_none() {
+        fixed[0] = Some(0.0);
+    }
+    if fixed[count - 1].is_none() {
+        fixed[count - 1] = Some(1.0);
+    }
+
+    let mut max_so_far = 0.0;
+    for position in fixed.iter_mut().flatten() {
+        if *position < max_so_far {
+            *position = max_so_far;
         }
+        max_so_far = *position;
+    }
+
+    let mut resolved = vec![0.0; count];
+    let mut last_index = 0;
+    for i in 0..count {
+        if let Some(position) = fixed[i] {
+            let start = resolved[last_index];
+            let steps = (i - last_index) as f64;
+            for (k, value) in resolved.iter_mut().enumerate().take(i).skip(last_index + 1) {
+                *value = start + (position - start) * (k - last_index) as f64 / steps;
+            }
+            resolved[i] = position;
+            last_index = i;
+        }
+    }
 
-        let mut print_spectrum = PrintSpectrum::Yes;
+    resolved
+}
 
-        let mix = get_mixing_function(matches.value_of("colorspace").expect("required argument"));
+/// Build a color scale from the color stop and midpoint hint arguments.
+fn color_scale_from_args(matches: &ArgMatches, config: &Config) -> Result<ColorScale> {
+    let mut print_spectrum = PrintSpectrum::Yes;
 
-        let colors = matches
-            .values_of("color")
-            .expect("required argument")
-            .map(|color| ColorArgIterator::from_color_arg(config, color, &mut print_spectrum));
+    let args = matches
+        .values_of("color")
+        .expect("required argument")
+        .map(parse_gradient_arg)
+        .collect::<Result<Vec<_>>>()?;
 
-        let color_count = colors.len();
-        if color_count < 2 {
-            return Err(PastelError::GradientColorCountMustBeLargerThanOne);
+    let mut colors = vec![];
+    let mut positions = vec![];
+    // midpoint hints, together with the index of the color stop before the hint
+    let mut hints: Vec<(&str, usize, f64)> = vec![];
+    for arg in args {
+        match arg {
+            GradientArg::Stop(color, position) => {
+                colors.push(ColorArgIterator::from_color_arg(
+                    config,
+                    color,
+                    &mut print_spectrum,
+                )?);
+                positions.push(position);
+            }
+            GradientArg::Hint(arg, position) => {
+                let follows_hint = hints
+                    .last()
+                    .map_or(false, |&(_, i, _)| i + 1 == colors.len());
+                if colors.is_empty() || follows_hint {
+                    return Err(PastelError::InvalidGradientHint(
+                        arg.into(),
+                        "a hint must be placed between two color stops".into(),
+                    ));
+                }
+                hints.push((arg, colors.len() - 1, position));
+            }
         }
+    }
 
-        let mut color_scale = ColorScale::empty();
+    if let Some(&(arg, index, _)) = hints.last() {
+        if index + 1 == colors.len() {
+            return Err(PastelError::InvalidGradientHint(
+                arg.into(),
+                "a hint must be placed between two color stops".into(),
+            ));
+        }
+    }
+
+    if colors.len() < 2 {
+        return Err(PastelError::GradientColorCountMustBeLargerThanOne);
+    }
 
-        for (i, color) in colors.enumerate() {
-            let position = Fraction::from(i as f64 / (color_count as f64 - 1.0));
+    let positions = resolve_positions(&positions);
 
-            color_scale.add_stop(color?, position);
+    for &(arg, index, hint) in &hints {
+        if hint <= positions[index] || hint >= positions[index + 1] {
+            return Err(PastelError::InvalidGradientHint(
+                arg.into(),
+                format!(
+                    "the hint must lie between the positions of the neighboring color stops \
+                     ({} and {})",
+                    positions[index],
+                    positions[index + 1]
+                ),
+            ));
         }
+    }
+
+    let mut color_scale = ColorScale::empty();
+
+    // Like in CSS, the first and the last color extend to the start and the end of the gradient
+    let first = (colors[0].clone(), positions[0]);
+    let last = (
+        colors[colors.len() - 1].clone(),
+        positions[positions.len() - 1],
+    );
+    if first.1 > 0.0 {
+        color_scale.add_stop(first.0, Fraction::from(0.0));
+    }
+
+    for (color, position) in colors.into_iter().zip(positions) {
+        color_scale.add_hard_stop(color, Fraction::from(position));
+    }
+
+    if last.1 < 1.0 {
+        color_scale.add_stop(last.0, Fraction::from(1.0));
+    }
+
+    for (_, _, hint) in hints {
+        color_scale.add_hint(Fraction::from(hint));
+    }
+
+    Ok(color_scale)
+}
+
+/// Sample the color scale at `count` evenly spaced positions, either with the given mixing
+/// function or with a spline through all color stops.
+pub(super) fn sample_color_scale(
+    color_scale: &ColorScale,
+    count: usize,
+    spline: Option<Spline>,
+    mix: &MixingFunction,
+) -> Result<Vec<Color>> {
+    if count < 2 {
+        return Err(PastelError::GradientNumberMustBeLargerThanOne);
+    }
 
-        for i in 0..count {
+    (0..count)
+        .map(|i| {
             let position = Fraction::from(i as f64 / (count as f64 - 1.0));
 
-            let color = color_scale.sample(position, &mix).expect("gradient color");
+            match spline {
+                Some(spline) => color_scale.sample_spline(position, spline),
+                None => color_scale.sample(position, mix),
+            }
+            .ok_or(PastelError::CouldNotSampleGradient)
+        })
+        .collect()
+}
+
+impl GenericCommand for GradientCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let count = matches.value_of("number").expect("required argument");
+        let count = count
+            .parse::<usize>()
+            .map_err(|_| PastelError::CouldNotParseNumber(count.into()))?;
+
+        let mix = get_mixing_function(
+            matches.value_of("colorspace").expect("required argument"),
+            get_hue_interpolation(matches.value_of("hue").expect("required argument")),
+            !matches.is_present("no-premultiply"),
+        );
+
+        let spline = match matches
+            .value_of("interpolation")
+            .expect("required argument")
+        {
+            "linear" => None,
+            "bspline" => Some(Spline::Basis),
+            "catmull-rom" => Some(Spline::CatmullRom),
+            "monotone" => Some(Spline::MonotoneCubic),
+            _ => unreachable!("Unknown interpolation method"),
+        };
+
+        let easing = matches.value_of("easing").expect("required argument");
+        let easing = easing
+            .parse::<Easing>()
+            .map_err(|_| PastelError::UnknownEasingFunction(easing.into()))?;
+
+        let mut color_scale = match matches.value_of("preset") {
+            Some(name) => colormap_by_name(name)
+                .ok_or_else(|| PastelError::UnknownColormap(name.into()))?
+                .color_scale(),
+            None => color_scale_from_args(matches, config)?,
+        };
+
+        color_scale
+            .set_easing(easing)
+            .set_correct_lightness(matches.is_present("correct-lightness"));
 
+        for color in sample_color_scale(&color_scale, count, spline, &mix)? {
             out.show_color(config, &color)?;
         }
 
@@ -0,0 +1,258 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::commands::prelude::*;
+use crate::utility::similar_colors;
+
+use pastel::parser::{find_colors, find_colors_with_names};
+
+pub struct GrepCommand;
+
+/// A color literal that has been found in a file.
+struct Occurrence {
+    line: usize,
+    column: usize,
+    text: String,
+    color: Color,
+}
+
+fn find_occurrences(text: &str, names: bool) -> Vec<Occurrence> {
+    let mut occurrences = vec![];
+
+    let matches = if names {
+        find_colors_with_names(text)
+    } else {
+        find_colors(text)
+    };
+
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut position = 0;
+    for m in matches {
+        for (i, c) in text[position..m.start].char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_start = position + i + 1;
+            }
+        }
+        position = m.start;
+
+        occurrences.push(Occurrence {
+            line,
+            column: text[line_start..m.start].chars().count() + 1,
+            text: m.text.to_string(),
+            color: m.color,
+        });
+    }
+
+    occurrences
+}
+
+/// Collect all files in the given paths, descending into directories. Hidden files and
+/// directories (like `.git`) are skipped. Symbolic links to directories are not followed, as they
+/// could lead to cycles.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let hidden = entry
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.starts_with('.'));
+        let symlinked_dir = entry.is_dir()
+            && std::fs::symlink_metadata(&entry)
+                .map_or(false, |metadata| metadata.file_type().is_symlink());
+        if !hidden && !symlinked_dir {
+            collect_files(&entry, files)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Files with a NUL byte in the first block are considered to be binary files (like in Git).
+fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(8000).any(|&byte| byte == 0)
+}
+
+/// Group colors whose distance is below the threshold (single-linkage clustering). Only groups
+/// with more than one color are returned.
+fn near_duplicates(colors: &[Color], threshold: f64) -> Vec<Vec<usize>> {
+    let mut cluster: Vec<usize> = (0..colors.len()).collect();
+
+    fn root(cluster: &mut [usize], mut i: usize) -> usize {
+        while cluster[i] != i {
+            cluster[i] = cluster[cluster[i]];
+            i = cluster[i];
+        }
+        i
+    }
+
+    for i in 0..colors.len() {
+        for j in i + 1..colors.len() {
+            if colors[i].distance_delta_e_ciede2000(&colors[j]) < threshold {
+                let (a, b) = (root(&mut cluster, i), root(&mut cluster, j));
+                cluster[b] = a;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of_root: Vec<Option<usize>> = vec![None; colors.len()];
+    for i in 0..colors.len() {
+        let r = root(&mut cluster, i);
+        match group_of_root[r] {
+            Some(g) => groups[g].push(i),
+            None => {
+                group_of_root[r] = Some(groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+impl GenericCommand for GrepCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let threshold = number_arg(matches, "threshold")?;
+        let names = matches.is_present("names");
+
+        let mut sources = vec![];
+        match matches.values_of("path") {
+            Some(paths) => {
+                let mut files = vec![];
+                for path in paths {
+                    collect_files(Path::new(path), &mut files)?;
+                }
+                for file in files {
+                    // unreadable files, binary files and files that are not UTF-8 encoded are
+                    // skipped
+                    let content = match std::fs::read(&file) {
+                        Ok(content) if !is_binary(&content) => content,
+                        _ => continue,
+                    };
+                    if let Ok(text) = String::from_utf8(content) {
+                        sources.push((file.display().to_string(), text));
+                    }
+                }
+            }
+            None => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|_| PastelError::CouldNotReadFromStdin)?;
+                sources.push(("(standard input)".to_string(), text));
+            }
+        }
+
+        // distinct colors (by hex value) and their number of occurrences
+        let mut inventory: Vec<(String, Color, usize)> = vec![];
+
+        for (name, text) in &sources {
+            for occurrence in find_occurrences(text, names) {
+                let color = &occurrence.color;
+                let hex = color.to_rgb_hex_string(true);
+
+                let nearest = similar_colors(color)[0];
+                let approximate = if nearest.color == *color { "" } else { "~" };
+
+                writeln!(
+                    out.handle,
+                    "{}:{}:{}: {} {} {}{}",
+                    name,
+                    occurrence.line,
+                    occurrence.column,
+                    occurrence.text,
+                    config
+                        .brush
+                        .paint(&hex, color.text_color().ansi_style().on(color)),
+                    approximate,
+                    nearest.name
+                )?;
+
+                match inventory.iter_mut().find(|(h, _, _)| *h == hex) {
+                    Some((_, _, count)) => *count += 1,
+                    None => inventory.push((hex, color.clone(), 1)),
+                }
+            }
+        }
+
+        if threshold > 0.0 {
+            let colors: Vec<Color> = inventory.iter().map(|(_, c, _)| c.clone()).collect();
+            let groups = near_duplicates(&colors, threshold);
+
+            if !groups.is_empty() {
+                writeln!(out.handle)?;
+                writeln!(out.handle, "Near-duplicate colors (ΔE < {}):", threshold)?;
+                for group in groups {
+                    let members: Vec<String> = group
+                        .iter()
+                        .map(|&i| format!("{} ({}x)", inventory[i].0, inventory[i].2))
+                        .collect();
+                    writeln!(out.handle, "  {}", members.join(", "))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_and_column() {
+        let occurrences = find_occurrences("a: #fff;\nb: red; c: ümlaut blue\n", true);
+
+        let positions: Vec<(usize, usize)> =
+            occurrences.iter().map(|o| (o.line, o.column)).collect();
+        assert_eq!(vec![(1, 4), (2, 4), (2, 19)], positions);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_are_not_followed() {
+        let dir = std::env::temp_dir().join(format!("pastel-grep-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("sub").join("a.css"), "a { color: #fff; }").unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("sub").join("loop")).unwrap();
+
+        let mut files = vec![];
+        collect_files(&dir, &mut files).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(vec![dir.join("sub").join("a.css")], files);
+    }
+
+    #[test]
+    fn binary_files() {
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_binary(b"a { color: #fff; }"));
+    }
+
+    #[test]
+    fn cluster_near_duplicates() {
+        let colors = [
+            Color::from_rgb(255, 0, 0),
+            Color::from_rgb(0, 0, 255),
+            Color::from_rgb(254, 1, 0),
+            Color::from_rgb(0, 0, 250),
+            Color::from_rgb(0, 255, 0),
+        ];
+
+        assert_eq!(vec![vec![0, 2], vec![1, 3]], near_duplicates(&colors, 3.0));
+        assert!(near_duplicates(&colors, 0.1).is_empty());
+    }
+}
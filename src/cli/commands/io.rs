@@ -1,14 +1,20 @@
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, BufReader, Stdin};
+use std::ops::Range;
+use std::sync::atomic::Ordering;
 
 use clap::{ArgMatches, Values};
 
 use crate::colorpicker::{print_colorspectrum, run_external_colorpicker};
-use crate::config::Config;
-use crate::{PastelError, Result};
+use crate::config::{ColorLocation, Config, OnError};
+use crate::output::Output;
+use crate::{write_stderr, PastelError, Result};
 
-use pastel::parser::parse_color;
+use pastel::parser::{parse_color, try_parse_color, ParseError};
 use pastel::Color;
 
+use rayon::prelude::*;
+use serde_json::Value;
+
 pub fn number_arg(matches: &ArgMatches, name: &str) -> Result<f64> {
     let value_str = matches.value_of(name).expect("required argument");
     value_str
@@ -22,9 +28,495 @@ pub enum PrintSpectrum {
     No,
 }
 
+/// Remove a trailing comment (`# …` or `// …`) from a line of input. A `#` that is directly
+/// followed by another character is the start of a hex color, not a comment.
+fn strip_comment(line: &str) -> &str {
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, next)| next);
+        match (c, next) {
+            ('#', None) | ('/', Some('/')) => return &line[..i],
+            ('#', Some(next)) if next.is_whitespace() => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Split a line into words that are separated by whitespace, commas or semicolons. Separators
+/// within parentheses (as in `rgb(…, …, …)`) are ignored.
+fn split_words(line: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if depth == 0 && (c.is_whitespace() || c == ',' || c == ';') => {
+                if start < i {
+                    words.push(&line[start..i]);
+                }
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if start < line.len() {
+        words.push(&line[start..]);
+    }
+    words
+}
+
+/// A word or field of a line of input that could not be parsed as a color.
+#[derive(Debug, PartialEq)]
+struct InvalidColor {
+    text: String,
+    /// The column (in characters, starting at 1) at which the text starts
+    column: usize,
+    error: ParseError,
+}
+
+impl InvalidColor {
+    /// The text is a part of the given line (or the whole line).
+    fn new(line: &str, text: &str, error: ParseError) -> Self {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(line.as_ptr() as usize)
+            .filter(|&offset| offset <= line.len())
+            .unwrap_or(0);
+        InvalidColor {
+            text: text.into(),
+            column: line[..offset].chars().count() + 1,
+            error,
+        }
+    }
+}
+
+/// Parse all colors on a line of input. Blank lines and comments do not contain any colors. Each
+/// word is parsed separately, such that the valid colors can be kept if some words are invalid.
+fn parse_line(line: &str) -> Vec<std::result::Result<Color, InvalidColor>> {
+    let words = strip_comment(line).trim();
+    if words.is_empty() {
+        return vec![];
+    }
+
+    // The whole line can be a single color, as in '119, 136, 153'
+    if let Some(color) = parse_color(words) {
+        return vec![Ok(color)];
+    }
+
+    split_words(words)
+        .into_iter()
+        .map(|word| try_parse_color(word).map_err(|err| InvalidColor::new(line, word, err)))
+        .collect()
+}
+
+/// The byte ranges of the fields in a line. Without a delimiter, fields are separated by runs of
+/// whitespace. Delimiters within double quotes or parentheses are ignored.
+fn field_ranges(line: &str, delimiter: Option<&str>) -> Vec<Range<usize>> {
+    let mut fields = vec![];
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quoted = false;
+
+    let mut i = 0;
+    while let Some(c) = line[i..].chars().next() {
+        let separator = match delimiter {
+            _ if quoted || depth > 0 => None,
+            Some(delimiter) if line[i..].starts_with(delimiter) => Some(delimiter.len()),
+            None if c.is_whitespace() => Some(c.len_utf8()),
+            _ => None,
+        };
+
+        match (c, separator) {
+            (_, Some(length)) => {
+                fields.push(start..i);
+                i += length;
+                start = i;
+                continue;
+            }
+            ('"', _) => quoted = !quoted,
+            ('(', _) => depth += 1,
+            (')', _) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    fields.push(start..line.len());
+
+    if delimiter.is_none() {
+        fields.retain(|field| !field.is_empty());
+    }
+    fields
+}
+
+/// The byte range of the color within a field, without surrounding whitespace and quotes.
+fn color_range(line: &str, field: Range<usize>) -> Range<usize> {
+    let text = &line[field.clone()];
+    let start = field.start + (text.len() - text.trim_start().len());
+    let end = field.end - (text.len() - text.trim_end().len());
+
+    let text = &line[start..end];
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        start + 1..end - 1
+    } else {
+        start..end
+    }
+}
+
+/// Convert a path like `theme.colors.0` or `theme.colors[0]` to a JSON pointer. A path that
+/// starts with a `/` already is a JSON pointer (RFC 6901), in which keys can contain dots.
+fn json_pointer(path: &str) -> String {
+    if path.starts_with('/') {
+        return path.into();
+    }
+
+    path.trim_start_matches('$')
+        .replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|key| !key.is_empty())
+        .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// The rest of a line of input that contains a single color (`--field`, `--json-path`).
+pub enum Context {
+    /// The text before and after the color. The output is quoted if it contains the delimiter.
+    Text {
+        before: String,
+        after: String,
+        delimiter: Option<String>,
+    },
+    /// A JSON document and the pointer to the color value
+    Json(Value, String),
+}
+
+impl Context {
+    /// Insert the output for the color into the line.
+    pub fn splice(self, output: &str) -> String {
+        match self {
+            Context::Text {
+                before,
+                after,
+                delimiter: Some(delimiter),
+            } if output.contains(&delimiter) => format!("{}\"{}\"{}", before, output, after),
+            Context::Text { before, after, .. } => format!("{}{}{}", before, output, after),
+            Context::Json(mut json, pointer) => {
+                if let Some(value) = json.pointer_mut(&pointer) {
+                    *value = Value::String(output.into());
+                }
+                json.to_string()
+            }
+        }
+    }
+}
+
+/// Find the color in a line of input, according to `--field` or `--json-path`. Blank lines and
+/// comments do not contain a color.
+fn embedded_color(
+    line: &str,
+    location: &ColorLocation,
+) -> std::result::Result<Option<(Color, Context)>, InvalidColor> {
+    if strip_comment(line).trim().is_empty() {
+        return Ok(None);
+    }
+
+    let invalid_line = |message: String| {
+        let err = ParseError {
+            offset: 0,
+            message,
+            suggestion: None,
+        };
+        InvalidColor::new(line, line, err)
+    };
+
+    match location {
+        ColorLocation::Line => unreachable!("not an embedded color"),
+        ColorLocation::Field(n, delimiter) => {
+            let fields = field_ranges(line, delimiter.as_deref());
+            let field = fields.get(n - 1).cloned().ok_or_else(|| {
+                invalid_line(format!("expected {} fields, found {}", n, fields.len()))
+            })?;
+
+            let range = color_range(line, field);
+            let text = &line[range.clone()];
+            let color = try_parse_color(text).map_err(|err| InvalidColor::new(line, text, err))?;
+
+            // quoted fields stay quoted
+            let quoted = line[..range.start].ends_with('"');
+            let context = Context::Text {
+                before: line[..range.start].into(),
+                after: line[range.end..].into(),
+                delimiter: delimiter.clone().filter(|_| !quoted),
+            };
+            Ok(Some((color, context)))
+        }
+        ColorLocation::JsonPath(path) => {
+            let json: Value = serde_json::from_str(line)
+                .map_err(|err| invalid_line(format!("invalid JSON: {}", err)))?;
+
+            let pointer = json_pointer(path);
+            let text = json
+                .pointer(&pointer)
+                .and_then(Value::as_str)
+                .ok_or_else(|| invalid_line(format!("no string value at '{}'", path)))?;
+            // the string value is not a part of the line, report the column of the whole line
+            let color = try_parse_color(text).map_err(|err| InvalidColor {
+                text: text.into(),
+                column: 1,
+                error: err,
+            })?;
+
+            Ok(Some((color, Context::Json(json, pointer))))
+        }
+    }
+}
+
+/// A line of standard input.
+pub enum Line {
+    Colors(Vec<Color>),
+    /// A color within the line (`--field`, `--json-path`)
+    Embedded(Color, Context),
+    /// A line that is copied to the output unchanged (`--on-error passthrough`)
+    Passthrough(String),
+}
+
+/// Reads the colors on standard input, line by line. Lines that can not be parsed are handled
+/// according to the `--on-error` policy.
+pub struct StdinLines<'a> {
+    config: &'a Config<'a>,
+    reader: BufReader<Stdin>,
+    buffer: Vec<u8>,
+    line_number: usize,
+}
+
+impl<'a> StdinLines<'a> {
+    pub fn new(config: &'a Config) -> Result<Self> {
+        use atty::Stream;
+        if atty::is(Stream::Stdin) {
+            return Err(PastelError::ColorArgRequired);
+        }
+        Ok(StdinLines {
+            config,
+            reader: BufReader::with_capacity(1 << 16, io::stdin()),
+            buffer: vec![],
+            line_number: 0,
+        })
+    }
+
+    /// Whether reading the next line might block, because all buffered input has been consumed.
+    pub fn needs_input(&self) -> bool {
+        self.reader.buffer().is_empty()
+    }
+}
+
+impl Iterator for StdinLines<'_> {
+    type Item = Result<Line>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err.into())),
+            }
+            self.line_number += 1;
+
+            let line = String::from_utf8_lossy(&self.buffer);
+            let line = line.trim_end_matches(&['\n', '\r'][..]);
+            let passthrough = self.config.on_error == OnError::Passthrough;
+
+            let (parsed, invalid) = match &self.config.color_location {
+                ColorLocation::Line => {
+                    let mut colors = vec![];
+                    let mut invalid = vec![];
+                    for result in parse_line(line) {
+                        match result {
+                            Ok(color) => colors.push(color),
+                            Err(err) => invalid.push(err),
+                        }
+                    }
+                    let parsed = if colors.is_empty() {
+                        None
+                    } else {
+                        Some(Line::Colors(colors))
+                    };
+                    (parsed, invalid)
+                }
+                location => match embedded_color(line, location) {
+                    Ok(found) => (
+                        found.map(|(color, context)| Line::Embedded(color, context)),
+                        vec![],
+                    ),
+                    Err(err) => (None, vec![err]),
+                },
+            };
+
+            // The policy is applied to each invalid word. The valid colors of the line are kept,
+            // unless the line is copied to the output unchanged.
+            if !invalid.is_empty() {
+                if self.config.on_error == OnError::Fail {
+                    let first = invalid.into_iter().next().expect("invalid word");
+                    return Some(Err(PastelError::InvalidInputLine(
+                        self.line_number,
+                        first.column,
+                        first.text,
+                        first.error,
+                    )));
+                }
+
+                self.config.invalid_input.store(true, Ordering::Relaxed);
+                match self.config.on_error {
+                    OnError::Warn => {
+                        for word in invalid {
+                            write_stderr(
+                                Color::yellow(),
+                                "pastel warning",
+                                &format!(
+                                    "line {}, column {}: could not parse '{}': {}",
+                                    self.line_number, word.column, word.text, word.error
+                                ),
+                            );
+                        }
+                    }
+                    OnError::Passthrough => return Some(Ok(Line::Passthrough(line.into()))),
+                    _ => {}
+                }
+            }
+
+            match parsed {
+                Some(parsed) => return Some(Ok(parsed)),
+                None if passthrough => return Some(Ok(Line::Passthrough(line.into()))),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Write the output for a line of standard input.
+fn write_line<F>(out: &mut Output, line: Line, f: &F) -> Result<()>
+where
+    F: Fn(&mut Output, &Color) -> Result<()>,
+{
+    match line {
+        Line::Colors(colors) => {
+            for color in &colors {
+                f(out, color)?;
+            }
+        }
+        Line::Embedded(color, context) => {
+            let mut buffer = vec![];
+            f(&mut Output::new(&mut buffer), &color)?;
+            let output = String::from_utf8_lossy(&buffer);
+            writeln!(
+                out.handle,
+                "{}",
+                context.splice(output.trim_end_matches('\n'))
+            )?;
+        }
+        Line::Passthrough(line) => writeln!(out.handle, "{}", line)?,
+    }
+
+    Ok(())
+}
+
+/// The maximum number of lines that are processed in parallel (`--jobs`).
+const BATCH_SIZE: usize = 4096;
+
+/// Process the lines of standard input in batches on several threads. The output of each line
+/// is collected in a buffer, such that it can be written in the original order.
+fn write_lines_parallel<F>(config: &Config, out: &mut Output, f: &F) -> Result<()>
+where
+    F: Fn(&mut Output, &Color) -> Result<()> + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(config.jobs)
+        .build()
+        .map_err(|err| PastelError::IoError(io::Error::new(io::ErrorKind::Other, err)))?;
+
+    let mut lines = StdinLines::new(config)?;
+    loop {
+        let mut batch = vec![];
+        let mut error = None;
+        let mut finished = false;
+        while batch.len() < BATCH_SIZE {
+            match lines.next() {
+                Some(Ok(line)) => batch.push(line),
+                Some(Err(err)) => error = Some(err),
+                None => finished = true,
+            }
+            // Lines that have been read are processed before waiting for more input
+            if error.is_some() || finished || lines.needs_input() {
+                break;
+            }
+        }
+
+        let outputs: Vec<Result<Vec<u8>>> = pool.install(|| {
+            batch
+                .into_par_iter()
+                .map(|line| {
+                    let mut buffer = vec![];
+                    write_line(&mut Output::new(&mut buffer), line, f)?;
+                    Ok(buffer)
+                })
+                .collect()
+        });
+        for output in outputs {
+            out.handle.write_all(&output?)?;
+        }
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+        if finished {
+            return Ok(());
+        }
+        out.handle.flush()?;
+    }
+}
+
+/// Run `f` for every color that is given on the command line or on standard input. Lines of
+/// standard input that are passed through (see `--on-error`) are written to the output as is.
+/// For colors within a line (`--field`, `--json-path`), the output of `f` is inserted into the
+/// line.
+pub fn for_each_color<'a, F>(
+    config: &'a Config,
+    args: Option<Values<'a>>,
+    out: &mut Output,
+    f: F,
+) -> Result<()>
+where
+    F: Fn(&mut Output, &Color) -> Result<()> + Sync,
+{
+    if args.is_some() {
+        for color in ColorArgIterator::from_args(config, args)? {
+            f(out, &color?)?;
+        }
+        return Ok(());
+    }
+
+    if config.jobs != 1 && !config.interactive_mode {
+        return write_lines_parallel(config, out, &f);
+    }
+
+    let mut lines = StdinLines::new(config)?;
+    loop {
+        // Make the output visible before waiting for more input
+        if lines.needs_input() {
+            out.handle.flush()?;
+        }
+        match lines.next() {
+            Some(line) => write_line(out, line?, &f)?,
+            None => return Ok(()),
+        }
+    }
+}
+
 pub enum ColorArgIterator<'a> {
     FromPositionalArguments(&'a Config<'a>, Values<'a>, PrintSpectrum),
-    FromStdin,
+    FromStdin(StdinLines<'a>, std::vec::IntoIter<Color>),
 }
 
 impl<'a> ColorArgIterator<'a> {
@@ -35,13 +527,10 @@ impl<'a> ColorArgIterator<'a> {
                 positionals,
                 PrintSpectrum::Yes,
             )),
-            None => {
-                use atty::Stream;
-                if atty::is(Stream::Stdin) {
-                    return Err(PastelError::ColorArgRequired);
-                }
-                Ok(ColorArgIterator::FromStdin)
-            }
+            None => Ok(ColorArgIterator::FromStdin(
+                StdinLines::new(config)?,
+                vec![].into_iter(),
+            )),
         }
     }
 
@@ -60,7 +549,7 @@ impl<'a> ColorArgIterator<'a> {
 
         let line = line.trim();
 
-        parse_color(line).ok_or_else(|| PastelError::ColorParseError(line.to_string()))
+        try_parse_color(line).map_err(|err| PastelError::ColorParseError(line.into(), Some(err)))
     }
 
     pub fn from_color_arg(
@@ -78,9 +567,8 @@ impl<'a> ColorArgIterator<'a> {
                 let color_str = run_external_colorpicker(config.colorpicker)?;
                 ColorArgIterator::from_color_arg(config, &color_str, print_spectrum)
             }
-            color_str => {
-                parse_color(color_str).ok_or_else(|| PastelError::ColorParseError(color_str.into()))
-            }
+            color_str => try_parse_color(color_str)
+                .map_err(|err| PastelError::ColorParseError(color_str.into(), Some(err))),
         }
     }
 }
@@ -98,11 +586,139 @@ impl<'a> Iterator for ColorArgIterator<'a> {
                 .next()
                 .map(|color_arg| Self::from_color_arg(config, color_arg, print_spectrum)),
 
-            ColorArgIterator::FromStdin => match Self::color_from_stdin() {
-                Ok(color) => Some(Ok(color)),
-                Err(PastelError::CouldNotReadFromStdin) => None,
-                err @ Err(_) => Some(err),
+            // Commands that collect all colors first (like 'sort-by') can not pass lines through,
+            // these lines are skipped
+            ColorArgIterator::FromStdin(ref mut lines, ref mut colors) => loop {
+                if let Some(color) = colors.next() {
+                    return Some(Ok(color));
+                }
+                match lines.next()? {
+                    Ok(Line::Colors(line_colors)) => *colors = line_colors.into_iter(),
+                    Ok(Line::Embedded(color, _)) => return Some(Ok(color)),
+                    Ok(Line::Passthrough(_)) => {}
+                    Err(err) => return Some(Err(err)),
+                }
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines() {
+        assert_eq!("", strip_comment("# palette"));
+        assert_eq!("#ff0000 ", strip_comment("#ff0000 # primary"));
+        assert_eq!("red ", strip_comment("red // primary"));
+        assert_eq!("#f00", strip_comment("#f00"));
+
+        assert!(parse_line("   ").is_empty());
+        assert!(parse_line("// colors").is_empty());
+    }
+
+    #[test]
+    fn multiple_colors_per_line() {
+        let parse = |line| -> Vec<std::result::Result<Color, (String, usize)>> {
+            parse_line(line)
+                .into_iter()
+                .map(|result| result.map_err(|err| (err.text, err.column)))
+                .collect()
+        };
+
+        assert_eq!(
+            vec![Ok(Color::from_rgb(119, 136, 153))],
+            parse("119, 136, 153")
+        );
+        assert_eq!(
+            vec![
+                Ok(Color::red()),
+                Ok(Color::from_rgb(1, 2, 3)),
+                Ok(Color::from_rgb(0, 0, 255))
+            ],
+            parse("#ff0000, rgb(1, 2, 3);\tblue")
+        );
+        assert_eq!(
+            vec![
+                Ok(Color::red()),
+                Err(("nocolor".into(), 7)),
+                Ok(Color::from_rgb(0, 0, 255))
+            ],
+            parse("  red nocolor blue # comment")
+        );
+        // columns are counted in characters
+        assert_eq!(
+            vec![Err(("ä".into(), 1)), Err(("bläu".into(), 4))],
+            parse("ä, bläu")
+        );
+    }
+
+    #[test]
+    fn json_paths() {
+        assert_eq!("/theme/colors/0", json_pointer("theme.colors.0"));
+        assert_eq!("/theme/colors/0", json_pointer("$.theme.colors[0]"));
+        assert_eq!("/a~1b/c", json_pointer("a/b.c"));
+        assert_eq!("/theme/color.primary", json_pointer("/theme/color.primary"));
+    }
+
+    #[test]
+    fn fields() {
+        let fields = |line: &'static str, delimiter| -> Vec<&str> {
+            field_ranges(line, delimiter)
+                .into_iter()
+                .map(|range| &line[range])
+                .collect()
+        };
+
+        assert_eq!(vec!["a", "#fff", "c"], fields("  a \t#fff c", None));
+        assert_eq!(vec!["a", "", " b c"], fields("a,, b c", Some(",")));
+        assert_eq!(
+            vec!["x", "\"rgb(1, 2, 3)\"", " hsl(0, 0%, 0%)"],
+            fields("x,\"rgb(1, 2, 3)\", hsl(0, 0%, 0%)", Some(","))
+        );
+        assert_eq!(vec!["a", "b"], fields("a::b", Some("::")));
+
+        let line = "red, \"#ff0000\" ,x";
+        assert_eq!("#ff0000", &line[color_range(line, 4..15)]);
+    }
+
+    #[test]
+    fn splice_colors() {
+        let location = ColorLocation::Field(2, Some(",".into()));
+        let (color, context) = embedded_color("primary, \"red\",main", &location)
+            .ok()
+            .flatten()
+            .unwrap();
+        assert_eq!(Color::red(), color);
+        assert_eq!("primary, \"#f00\",main", context.splice("#f00"));
+
+        let (_, context) = embedded_color("primary,red,main", &location)
+            .ok()
+            .flatten()
+            .unwrap();
+        assert_eq!(
+            "primary,\"rgb(255, 0, 0)\",main",
+            context.splice("rgb(255, 0, 0)")
+        );
+
+        let location = ColorLocation::JsonPath("theme.colors[1]".into());
+        let (color, context) =
+            embedded_color(r#"{"theme":{"colors":["red","blue"]},"id":1}"#, &location)
+                .ok()
+                .flatten()
+                .unwrap();
+        assert_eq!(Color::from_rgb(0, 0, 255), color);
+        assert_eq!(
+            r##"{"theme":{"colors":["red","#00f"]},"id":1}"##,
+            context.splice("#00f")
+        );
+
+        let location = ColorLocation::Field(3, None);
+        assert!(embedded_color("a b", &location).is_err());
+        assert!(embedded_color("# a comment", &location)
+            .ok()
+            .flatten()
+            .is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use std::io::{BufWriter, Write};
+
 use crate::config::Config;
 use crate::error::Result;
 use crate::output::Output;
@@ -5,34 +7,44 @@ use clap::ArgMatches;
 
 mod color_commands;
 mod colorcheck;
+mod colormap;
 mod distinct;
 mod format;
 mod gradient;
 mod gray;
+mod grep;
 mod io;
 mod list;
 mod paint;
 mod pick;
+mod pipeline;
 mod prelude;
 mod random;
+mod replace;
 mod show;
 mod sort;
+mod tokens;
 mod traits;
 
 use traits::{ColorCommand, GenericCommand};
 
 use colorcheck::ColorCheckCommand;
+use colormap::ColormapCommand;
 use distinct::DistinctCommand;
 use format::FormatCommand;
 use gradient::GradientCommand;
 use gray::GrayCommand;
+use grep::GrepCommand;
 use list::ListCommand;
 use paint::PaintCommand;
 use pick::PickCommand;
 use random::RandomCommand;
+use replace::ReplaceCommand;
 use sort::SortCommand;
+use tokens::TokensCommand;
 
-use io::ColorArgIterator;
+use io::for_each_color;
+pub use io::{ColorArgIterator, PrintSpectrum};
 
 pub enum Command {
     WithColor(Box<dyn ColorCommand>),
@@ -52,6 +64,8 @@ impl Command {
             "set" => Command::WithColor(Box::new(color_commands::SetCommand)),
             "complement" => Command::WithColor(Box::new(color_commands::ComplementCommand)),
             "mix" => Command::WithColor(Box::new(color_commands::MixCommand)),
+            "blend" => Command::WithColor(Box::new(color_commands::BlendCommand)),
+            "flatten" => Command::WithColor(Box::new(color_commands::FlattenCommand)),
             "to-gray" => Command::WithColor(Box::new(color_commands::ToGrayCommand)),
             "textcolor" => Command::WithColor(Box::new(color_commands::TextColorCommand)),
             "pick" => Command::Generic(Box::new(PickCommand)),
@@ -61,27 +75,41 @@ impl Command {
             "random" => Command::Generic(Box::new(RandomCommand)),
             "distinct" => Command::Generic(Box::new(DistinctCommand)),
             "gradient" => Command::Generic(Box::new(GradientCommand)),
+            "colormap" => Command::Generic(Box::new(ColormapCommand)),
             "paint" => Command::Generic(Box::new(PaintCommand)),
-            "format" => Command::WithColor(Box::new(FormatCommand)),
+            "format" => Command::Generic(Box::new(FormatCommand)),
             "colorcheck" => Command::Generic(Box::new(ColorCheckCommand)),
+            "tokens" => Command::Generic(Box::new(TokensCommand)),
+            "replace" => Command::Generic(Box::new(ReplaceCommand)),
+            "grep" => Command::Generic(Box::new(GrepCommand)),
             _ => unreachable!("Unknown subcommand"),
         }
     }
 
     pub fn execute(&self, matches: &ArgMatches, config: &Config) -> Result<()> {
         let stdout = std::io::stdout();
-        let mut stdout_lock = stdout.lock();
-        let mut out = Output::new(&mut stdout_lock);
+        let stdout_lock = stdout.lock();
+
+        // Output to a terminal is written line by line. Otherwise, it is buffered (and flushed
+        // whenever pastel waits for more input).
+        let mut handle: Box<dyn Write> = if config.interactive_mode {
+            Box::new(stdout_lock)
+        } else {
+            Box::new(BufWriter::with_capacity(1 << 16, stdout_lock))
+        };
 
-        match self {
+        let mut out = Output::new(&mut handle);
+        let result = match self {
             Command::Generic(cmd) => cmd.run(&mut out, matches, config),
-            Command::WithColor(cmd) => {
-                for color in ColorArgIterator::from_args(config, matches.values_of("color"))? {
-                    cmd.run(&mut out, matches, config, &color?)?;
-                }
+            Command::WithColor(cmd) => for_each_color(
+                config,
+                matches.values_of("color"),
+                &mut out,
+                |out, color| cmd.run(out, matches, config, color),
+            ),
+        };
 
-                Ok(())
-            }
-        }
+        handle.flush()?;
+        result
     }
 }
@@ -5,7 +5,6 @@ use crate::commands::prelude::*;
 use super::io::ColorArgIterator;
 
 use pastel::ansi::Style;
-use pastel::parser::parse_color;
 
 pub struct PaintCommand;
 
@@ -24,7 +23,12 @@ impl GenericCommand for PaintCommand {
         };
 
         let bg = if let Some(bg) = matches.value_of("on") {
-            Some(parse_color(bg).ok_or_else(|| PastelError::ColorParseError(bg.into()))?)
+            let mut print_spectrum = PrintSpectrum::Yes;
+            Some(ColorArgIterator::from_color_arg(
+                config,
+                bg,
+                &mut print_spectrum,
+            )?)
         } else {
             None
         };
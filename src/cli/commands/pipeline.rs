@@ -0,0 +1,107 @@
+use crate::cli::build_cli;
+use crate::commands::prelude::*;
+use crate::commands::Command;
+
+/// A sequence of color commands like `lighten 0.1 | saturate 0.2` that is applied to single
+/// colors, e.g. for `--exec` options.
+pub struct Pipeline {
+    steps: Vec<(Box<dyn ColorCommand>, ArgMatches)>,
+}
+
+/// Split a command line into words. Words can be quoted with single or double quotes.
+fn split_words(command: &str) -> Option<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+    words.extend(word);
+
+    Some(words)
+}
+
+impl Pipeline {
+    pub fn parse(pipeline: &str) -> Result<Self> {
+        let invalid = |reason: String| PastelError::InvalidPipeline(pipeline.into(), reason);
+
+        let mut steps = vec![];
+        for command in pipeline.split('|') {
+            let words = split_words(command).ok_or_else(|| invalid("unmatched quote".into()))?;
+            if words.is_empty() {
+                return Err(invalid("empty command".into()));
+            }
+
+            let matches = build_cli()
+                .try_get_matches_from(std::iter::once("pastel".to_string()).chain(words))
+                .map_err(|err| {
+                    let message = err.to_string();
+                    let first_line = message.lines().next().unwrap_or_default();
+                    invalid(first_line.trim_start_matches("error: ").into())
+                })?;
+
+            let (name, _) = matches.subcommand().expect("subcommand is required");
+            match Command::from_string(name) {
+                Command::WithColor(cmd) => steps.push((cmd, matches)),
+                Command::Generic(_) => {
+                    return Err(invalid(format!("'{}' is not a color command", name)));
+                }
+            }
+        }
+
+        Ok(Pipeline { steps })
+    }
+
+    /// Run all commands of the pipeline on the given color.
+    pub fn apply(&self, config: &Config, color: &Color) -> Result<Color> {
+        let mut color = color.clone();
+
+        for (cmd, matches) in &self.steps {
+            let (_, matches) = matches.subcommand().expect("subcommand is required");
+
+            let mut sink = std::io::sink();
+            let mut out = Output::collecting(&mut sink);
+            cmd.run(&mut out, matches, config, &color)?;
+
+            color = out
+                .into_colors()
+                .pop()
+                .ok_or(PastelError::PipelineWithoutResult)?;
+        }
+
+        Ok(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            Some(vec!["darken".into(), "0.1".into()]),
+            split_words(" darken  0.1 ")
+        );
+        assert_eq!(
+            Some(vec!["mix".into(), "rgb(0, 0, 255)".into()]),
+            split_words("mix 'rgb(0, 0, 255)'")
+        );
+        assert_eq!(Some(vec!["a".into(), "".into()]), split_words("a \"\""));
+        assert_eq!(None, split_words("mix 'red"));
+    }
+}
@@ -0,0 +1,295 @@
+use std::io::Read;
+
+use crate::commands::pipeline::Pipeline;
+use crate::commands::prelude::*;
+use crate::formats::{channel_by_name, Channel};
+use crate::utility::similar_colors;
+
+use pastel::parser::{find_colors, find_colors_with_names};
+use pastel::Format;
+
+pub struct ReplaceCommand;
+
+/// Format a number with at most `precision` decimal places and without trailing zeros.
+fn number(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+fn modern_alpha(alpha: f64) -> String {
+    if alpha == 1.0 {
+        "".into()
+    } else {
+        format!(" / {}", number(alpha, 3))
+    }
+}
+
+/// Format a hex color like the original literal: with or without the alpha channel, in the
+/// short form (if possible), and in upper or lower case.
+fn format_hex_like(original: &str, color: &Color) -> String {
+    let digits = original.trim_start_matches('#');
+    let rgba = color.to_rgba();
+
+    let with_alpha = matches!(digits.len(), 4 | 8) || rgba.alpha != 1.0;
+    let mut hex = if with_alpha {
+        format!("{:08x}", rgba.to_rgba_u32())
+    } else {
+        format!("{:06x}", rgba.to_u32())
+    };
+
+    let bytes: Vec<&str> = (0..hex.len() / 2).map(|i| &hex[2 * i..2 * i + 2]).collect();
+    let can_be_short = bytes.iter().all(|byte| byte[..1] == byte[1..]);
+    if matches!(digits.len(), 3 | 4) && can_be_short {
+        hex = bytes.iter().map(|byte| &byte[..1]).collect();
+    }
+
+    let uppercase = digits.chars().any(|c| c.is_ascii_uppercase())
+        && !digits.chars().any(|c| c.is_ascii_lowercase());
+    if uppercase {
+        hex = hex.to_uppercase();
+    }
+
+    let hash = if original.starts_with('#') { "#" } else { "" };
+    format!("{}{}", hash, hex)
+}
+
+/// Whether the alpha value of a color literal in functional notation is given as a percentage,
+/// as in `rgb(255 0 119 / 50%)` or `rgba(255, 0, 119, 50%)`.
+fn has_percentage_alpha(original: &str) -> bool {
+    let arguments = match original.split_once('(') {
+        Some((_, arguments)) => arguments.trim_end_matches(')'),
+        None => return false,
+    };
+
+    let alpha = match arguments.split_once('/') {
+        Some((_, alpha)) => alpha,
+        None => match arguments.split(',').nth(3) {
+            Some(alpha) => alpha,
+            None => return false,
+        },
+    };
+    alpha.trim().ends_with('%')
+}
+
+/// Write the alpha value (the last argument) as a percentage if the original literal does.
+fn keep_alpha_notation(original: &str, formatted: String, alpha: f64) -> String {
+    if alpha == 1.0 || !has_percentage_alpha(original) {
+        return formatted;
+    }
+
+    match formatted.rfind([',', '/']) {
+        Some(i) => format!("{} {}%)", &formatted[..=i], number(100.0 * alpha, 1)),
+        None => formatted,
+    }
+}
+
+/// Format the color in the notation of the original color literal.
+fn format_like(original: &str, color: &Color) -> String {
+    if original.starts_with('#') {
+        return format_hex_like(original, color);
+    }
+
+    let formatted = format_function_like(original, color);
+    keep_alpha_notation(original, formatted, color.to_rgba().alpha)
+}
+
+fn format_function_like(original: &str, color: &Color) -> String {
+    let function = original
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let legacy = original.contains(',');
+
+    match function.as_ref() {
+        "rgb" | "rgba" if legacy => color.to_rgb_string(Format::Spaces),
+        "rgb" | "rgba" => {
+            let rgba = color.to_rgba();
+            format!(
+                "rgb({} {} {}{})",
+                rgba.r,
+                rgba.g,
+                rgba.b,
+                modern_alpha(rgba.alpha)
+            )
+        }
+        "hsl" | "hsla" if legacy => color.to_hsl_string(Format::Spaces),
+        "hsl" | "hsla" => {
+            let hsla = color.to_hsla();
+            format!(
+                "hsl({} {}% {}%{})",
+                number(hsla.h, 1),
+                number(100.0 * hsla.s, 1),
+                number(100.0 * hsla.l, 1),
+                modern_alpha(hsla.alpha)
+            )
+        }
+        "hwb" => color.to_hwb_string(Format::Spaces),
+        "lab" if legacy => color.to_lab_string(Format::Spaces),
+        "lab" => {
+            let lab = color.to_lab();
+            format!(
+                "lab({} {} {}{})",
+                number(lab.l, 2),
+                number(lab.a, 2),
+                number(lab.b, 2),
+                modern_alpha(lab.alpha)
+            )
+        }
+        "lch" if legacy => color.to_lch_string(Format::Spaces),
+        "lch" => {
+            let lch = color.to_lch();
+            format!(
+                "lch({} {} {}{})",
+                number(lch.l, 2),
+                number(lch.c, 2),
+                number(lch.h, 2),
+                modern_alpha(lch.alpha)
+            )
+        }
+        _ if original.chars().all(|c| c.is_ascii_alphabetic()) => {
+            // named colors are kept if there is an exact match
+            match similar_colors(color).first() {
+                Some(nc) if nc.color == *color => nc.name.to_string(),
+                _ => color.to_rgb_hex_string(true),
+            }
+        }
+        _ => color.to_rgb_hex_string(true),
+    }
+}
+
+/// Replace all color literals in the text.
+fn replace_colors(
+    text: &str,
+    config: &Config,
+    pipeline: Option<&Pipeline>,
+    to: Option<&Channel>,
+    include_names: bool,
+) -> Result<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    let matches = if include_names {
+        find_colors_with_names(text)
+    } else {
+        find_colors(text)
+    };
+
+    for m in matches {
+        let color = match pipeline {
+            Some(pipeline) => pipeline.apply(config, &m.color)?,
+            None => m.color.clone(),
+        };
+
+        result.push_str(&text[last..m.start]);
+        match to {
+            Some(channel) => {
+                result.push_str(&channel.format(&color, config.background.as_ref(), None))
+            }
+            None => result.push_str(&format_like(m.text, &color)),
+        }
+        last = m.end;
+    }
+    result.push_str(&text[last..]);
+
+    Ok(result)
+}
+
+impl GenericCommand for ReplaceCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let pipeline = matches.value_of("exec").map(Pipeline::parse).transpose()?;
+        let to = matches
+            .value_of("to")
+            .map(|to| channel_by_name(to).ok_or_else(|| PastelError::UnknownFormatType(to.into())))
+            .transpose()?;
+        let include_names = matches.is_present("names");
+
+        let replace =
+            |text: &str| replace_colors(text, config, pipeline.as_ref(), to, include_names);
+
+        match matches.values_of("file") {
+            Some(files) => {
+                for file in files {
+                    let text = std::fs::read_to_string(file)?;
+                    let replaced = replace(&text)?;
+                    if matches.is_present("in-place") {
+                        if replaced != text {
+                            std::fs::write(file, replaced)?;
+                        }
+                    } else {
+                        write!(out.handle, "{}", replaced)?;
+                    }
+                }
+            }
+            None => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|_| PastelError::CouldNotReadFromStdin)?;
+                write!(out.handle, "{}", replace(&text)?)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_hex_notation() {
+        let color = Color::from_rgb(0x11, 0x22, 0x33);
+        assert_eq!("#123", format_hex_like("#abc", &color));
+        assert_eq!("#112233", format_hex_like("#aabbcc", &color));
+        assert_eq!("#123F", format_hex_like("#ABCD", &color));
+        assert_eq!("112233ff", format_hex_like("aabbccdd", &color));
+
+        let color = Color::from_rgb(0x12, 0x22, 0x33);
+        assert_eq!("#122233", format_hex_like("#abc", &color));
+    }
+
+    #[test]
+    fn keep_functional_notation() {
+        let color = Color::from_rgba(255, 0, 119, 0.5);
+        assert_eq!(
+            "rgba(255, 0, 119, 0.5)",
+            format_like("rgb(0, 0, 0)", &color)
+        );
+        assert_eq!("rgb(255 0 119 / 0.5)", format_like("rgb(0 0 0)", &color));
+        assert_eq!(
+            "hsl(332 100% 50% / 0.5)",
+            format_like("hsl(0 0% 0%)", &color)
+        );
+        assert_eq!("red", format_like("Blue", &Color::red()));
+        assert_eq!(
+            "#ff0077",
+            format_like("blue", &Color::from_rgb(255, 0, 119))
+        );
+    }
+
+    #[test]
+    fn keep_percentage_alpha() {
+        let color = Color::from_rgba(255, 0, 119, 0.25);
+        assert_eq!(
+            "rgb(255 0 119 / 25%)",
+            format_like("rgb(0 0 0 / 50%)", &color)
+        );
+        assert_eq!(
+            "rgba(255, 0, 119, 25%)",
+            format_like("rgba(0, 0, 0, 50%)", &color)
+        );
+        assert_eq!(
+            "rgba(255, 0, 119, 0.25)",
+            format_like("rgba(0, 0, 0, 0.5)", &color)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use serde_json::{Map, Value};
+
+use crate::commands::pipeline::Pipeline;
+use crate::commands::prelude::*;
+use crate::formats::channel_by_name;
+
+use pastel::parser::{parse_color, try_parse_color};
+
+pub struct TokensCommand;
+
+/// The JSON flavor of a design tokens file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TokenFormat {
+    /// W3C Design Tokens Community Group format (`$value`, `$type`, `$description`)
+    Dtcg,
+    /// Style Dictionary format (`value`, `type`, `comment`)
+    StyleDictionary,
+}
+
+impl TokenFormat {
+    fn value_key(self) -> &'static str {
+        match self {
+            TokenFormat::Dtcg => "$value",
+            TokenFormat::StyleDictionary => "value",
+        }
+    }
+
+    fn type_key(self) -> &'static str {
+        match self {
+            TokenFormat::Dtcg => "$type",
+            TokenFormat::StyleDictionary => "type",
+        }
+    }
+
+    fn description_key(self) -> &'static str {
+        match self {
+            TokenFormat::Dtcg => "$description",
+            TokenFormat::StyleDictionary => "comment",
+        }
+    }
+
+    /// Guess the format of a tokens file: DTCG files use `$value`, Style Dictionary files `value`.
+    fn detect(json: &Value) -> TokenFormat {
+        fn has_dtcg_value(json: &Value) -> bool {
+            match json {
+                Value::Object(map) => {
+                    map.contains_key("$value") || map.values().any(has_dtcg_value)
+                }
+                _ => false,
+            }
+        }
+
+        if has_dtcg_value(json) {
+            TokenFormat::Dtcg
+        } else {
+            TokenFormat::StyleDictionary
+        }
+    }
+}
+
+/// A color token: either a color literal or an alias (`{color.brand.500}`) of another token.
+#[derive(Debug, Clone)]
+enum Token {
+    Literal(String),
+    Alias(String),
+}
+
+impl Token {
+    fn from_value(value: &str) -> Token {
+        let trimmed = value.trim();
+        match trimmed
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            Some(path) => Token::Alias(path.trim().to_string()),
+            None => Token::Literal(trimmed.to_string()),
+        }
+    }
+}
+
+/// Whether a literal is written in an explicit color notation: a hex code with a leading `#`, a
+/// color function or a color name. Untyped tokens with bare numbers like `700` are font weights or
+/// sizes, even though they would also be accepted as hex codes.
+fn is_color_notation(literal: &str) -> bool {
+    literal.starts_with('#')
+        || literal.ends_with(')')
+        || literal.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Collect all color tokens of a tokens file, indexed by their dot-separated path.
+fn collect_tokens(
+    json: &Value,
+    format: TokenFormat,
+    path: &mut Vec<String>,
+    inherited_type: Option<&str>,
+    tokens: &mut HashMap<String, Token>,
+) {
+    let map = match json {
+        Value::Object(map) => map,
+        _ => return,
+    };
+
+    // the type of a token can be inherited from its group (DTCG)
+    let token_type = map
+        .get(format.type_key())
+        .and_then(Value::as_str)
+        .or(inherited_type);
+
+    if let Some(value) = map.get(format.value_key()) {
+        let is_color_type = token_type.map_or(true, |t| t == "color");
+        if let (true, Some(value)) = (is_color_type, value.as_str()) {
+            let token = Token::from_value(value);
+            let is_color = match &token {
+                Token::Literal(literal) => {
+                    is_color_notation(literal) && parse_color(literal).is_some()
+                }
+                Token::Alias(_) => true,
+            };
+            if token_type.is_some() || is_color {
+                tokens.insert(path.join("."), token);
+            }
+        }
+        return;
+    }
+
+    for (key, child) in map {
+        if key.starts_with('$') {
+            continue;
+        }
+        path.push(key.clone());
+        collect_tokens(child, format, path, token_type, tokens);
+        path.pop();
+    }
+}
+
+/// Resolve a (possibly aliased) token to a color.
+fn resolve(
+    tokens: &HashMap<String, Token>,
+    path: &str,
+    visited: &mut Vec<String>,
+) -> Result<Color> {
+    if visited.iter().any(|p| p == path) {
+        return Err(PastelError::CircularTokenAlias(path.into()));
+    }
+
+    // Style Dictionary also supports references to the value itself (`{color.red.value}`)
+    let token = tokens
+        .get(path)
+        .or_else(|| path.strip_suffix(".value").and_then(|p| tokens.get(p)))
+        .ok_or_else(|| PastelError::UnresolvedTokenAlias(path.into()))?;
+
+    match token {
+        Token::Literal(literal) => try_parse_color(literal)
+            .map_err(|err| PastelError::ColorParseError(literal.clone(), Some(err))),
+        Token::Alias(target) => {
+            visited.push(path.into());
+            resolve(tokens, target, visited)
+        }
+    }
+}
+
+/// Write the transformed color tokens back into the JSON tree and convert the keys to the
+/// output format.
+fn rewrite(
+    json: &mut Value,
+    input: TokenFormat,
+    output: TokenFormat,
+    path: &mut Vec<String>,
+    values: &HashMap<String, String>,
+) {
+    let map = match json {
+        Value::Object(map) => map,
+        _ => return,
+    };
+
+    let is_token = map.contains_key(input.value_key());
+    if is_token {
+        if let Some(value) = values.get(&path.join(".")) {
+            map.insert(input.value_key().into(), Value::String(value.clone()));
+        }
+    }
+
+    if input != output {
+        let renamed: Map<String, Value> = std::mem::take(map)
+            .into_iter()
+            .map(|(key, value)| {
+                let key = if key == input.value_key() && is_token {
+                    output.value_key().to_string()
+                } else if !is_token && !value.is_string() {
+                    // a group that happens to be called 'type' or 'comment'
+                    key
+                } else if key == input.type_key() {
+                    output.type_key().to_string()
+                } else if key == input.description_key() {
+                    output.description_key().to_string()
+                } else {
+                    key
+                };
+                (key, value)
+            })
+            .collect();
+        *map = renamed;
+    }
+
+    if is_token {
+        return;
+    }
+
+    for (key, child) in map.iter_mut() {
+        if key.starts_with('$') {
+            continue;
+        }
+        path.push(key.clone());
+        rewrite(child, input, output, path, values);
+        path.pop();
+    }
+}
+
+impl GenericCommand for TokensCommand {
+    fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()> {
+        let mut content = String::new();
+        match matches.value_of("file") {
+            None | Some("-") => {
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|_| PastelError::CouldNotReadFromStdin)?;
+            }
+            Some(file) => {
+                content = std::fs::read_to_string(file)?;
+            }
+        }
+
+        let mut json: Value = serde_json::from_str(&content)
+            .map_err(|err| PastelError::InvalidTokenFile(err.to_string()))?;
+
+        let input_format = TokenFormat::detect(&json);
+        let output_format = match matches.value_of("output-format") {
+            None => input_format,
+            Some("dtcg") => TokenFormat::Dtcg,
+            Some("style-dictionary") => TokenFormat::StyleDictionary,
+            Some(_) => unreachable!("Unknown token format"),
+        };
+
+        let pipeline = matches.value_of("exec").map(Pipeline::parse).transpose()?;
+        let to = matches.value_of("to").expect("required argument");
+        let channel =
+            channel_by_name(to).ok_or_else(|| PastelError::UnknownFormatType(to.into()))?;
+        let resolve_aliases = matches.is_present("resolve-aliases");
+
+        let mut tokens = HashMap::new();
+        collect_tokens(&json, input_format, &mut vec![], None, &mut tokens);
+
+        let mut values = HashMap::new();
+        for (path, token) in &tokens {
+            if let (Token::Alias(_), false) = (token, resolve_aliases) {
+                continue;
+            }
+
+            let color = resolve(&tokens, path, &mut vec![])?;
+            let color = match &pipeline {
+                Some(pipeline) => pipeline.apply(config, &color)?,
+                None => color,
+            };
+            values.insert(
+                path.clone(),
+                channel.format(&color, config.background.as_ref(), None),
+            );
+        }
+
+        rewrite(&mut json, input_format, output_format, &mut vec![], &values);
+
+        let output = serde_json::to_string_pretty(&json)
+            .map_err(|err| PastelError::InvalidTokenFile(err.to_string()))?;
+        writeln!(out.handle, "{}", output)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(json: &str) -> HashMap<String, Token> {
+        let json: Value = serde_json::from_str(json).unwrap();
+        let mut tokens = HashMap::new();
+        collect_tokens(
+            &json,
+            TokenFormat::detect(&json),
+            &mut vec![],
+            None,
+            &mut tokens,
+        );
+        tokens
+    }
+
+    #[test]
+    fn collect_and_resolve_dtcg() {
+        let tokens = tokens(
+            r##"{
+                "color": {
+                    "$type": "color",
+                    "brand": { "500": { "$value": "#ff0000" } },
+                    "primary": { "$value": "{color.brand.500}" }
+                },
+                "spacing": { "small": { "$type": "dimension", "$value": "4px" } }
+            }"##,
+        );
+
+        assert_eq!(2, tokens.len());
+        assert_eq!(
+            Color::red(),
+            resolve(&tokens, "color.primary", &mut vec![]).unwrap()
+        );
+    }
+
+    #[test]
+    fn collect_and_resolve_style_dictionary() {
+        let tokens = tokens(
+            r##"{
+                "color": {
+                    "base": { "red": { "value": "#ff0000" } },
+                    "font": { "value": "{color.base.red.value}" }
+                },
+                "size": { "small": { "value": "0.75" } }
+            }"##,
+        );
+
+        assert_eq!(2, tokens.len());
+        assert_eq!(
+            Color::red(),
+            resolve(&tokens, "color.font", &mut vec![]).unwrap()
+        );
+    }
+
+    #[test]
+    fn untyped_numbers_are_not_colors() {
+        let tokens = tokens(
+            r##"{
+                "font": { "weight": { "bold": { "value": "700" } } },
+                "size": { "value": "400" },
+                "color": { "value": "#700" }
+            }"##,
+        );
+
+        assert_eq!(1, tokens.len());
+        assert!(tokens.contains_key("color"));
+    }
+
+    #[test]
+    fn rewrite_group_named_type() {
+        let mut json: Value =
+            serde_json::from_str(r##"{ "font": { "type": { "base": { "value": "#ff0000" } } } }"##)
+                .unwrap();
+        rewrite(
+            &mut json,
+            TokenFormat::StyleDictionary,
+            TokenFormat::Dtcg,
+            &mut vec![],
+            &HashMap::new(),
+        );
+
+        assert_eq!(
+            serde_json::json!({ "font": { "type": { "base": { "$value": "#ff0000" } } } }),
+            json
+        );
+    }
+
+    #[test]
+    fn circular_aliases() {
+        let tokens = tokens(
+            r##"{
+                "a": { "$type": "color", "$value": "{b}" },
+                "b": { "$type": "color", "$value": "{a}" }
+            }"##,
+        );
+
+        assert!(matches!(
+            resolve(&tokens, "a", &mut vec![]),
+            Err(PastelError::CircularTokenAlias(_))
+        ));
+    }
+}
@@ -10,7 +10,7 @@ pub trait GenericCommand {
     fn run(&self, out: &mut Output, matches: &ArgMatches, config: &Config) -> Result<()>;
 }
 
-pub trait ColorCommand {
+pub trait ColorCommand: Sync {
     fn run(
         &self,
         out: &mut Output,
@@ -1,6 +1,28 @@
+use std::sync::atomic::AtomicBool;
+
 use pastel::ansi::Brush;
+use pastel::Color;
+
+/// What to do with lines of standard input that can not be parsed (`--on-error`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnError {
+    Fail,
+    Skip,
+    Warn,
+    Passthrough,
+}
+
+/// Where the color is located in a line of standard input (`--field`, `--json-path`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorLocation {
+    Line,
+    /// The n-th field (starting at 1), separated by the delimiter or by whitespace
+    Field(usize, Option<String>),
+    /// A string value in a JSON object, given by a dot-separated path
+    JsonPath(String),
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Config<'p> {
     pub padding: usize,
     pub colorpicker_width: usize,
@@ -8,4 +30,11 @@ pub struct Config<'p> {
     pub colorpicker: Option<&'p str>,
     pub interactive_mode: bool,
     pub brush: Brush,
+    pub background: Option<Color>,
+    pub on_error: OnError,
+    pub color_location: ColorLocation,
+    /// The number of threads that process colors from standard input (0: one per CPU core)
+    pub jobs: usize,
+    /// Set if a line of standard input could not be parsed (and was not treated as an error)
+    pub invalid_input: AtomicBool,
 }
@@ -1,9 +1,26 @@
 use crate::ansi;
+use pastel::parser::ParseError;
+
+/// The reason why a color could not be parsed, with a marker below the position of the error.
+fn describe_parse_error(color: &str, err: &ParseError) -> String {
+    let column = color.get(..err.offset).unwrap_or(color).chars().count();
+    let mut message = format!(
+        "{}\n\n    {}\n    {}^",
+        err.message,
+        color,
+        " ".repeat(column)
+    );
+    if let Some(suggestion) = &err.suggestion {
+        message.push_str(&format!("\n\nDid you mean '{}'?", suggestion));
+    }
+    message
+}
 
 #[derive(Debug)]
 pub enum PastelError {
     UnknownColorMode(String),
-    ColorParseError(String),
+    ColorParseError(String, Option<ParseError>),
+    InvalidInputLine(usize, usize, String, ParseError),
     ColorInvalidUTF8,
     CouldNotReadFromStdin,
     ColorArgRequired,
@@ -11,8 +28,25 @@ pub enum PastelError {
     StdoutClosed,
     GradientNumberMustBeLargerThanOne,
     GradientColorCountMustBeLargerThanOne,
+    CouldNotParseStopPosition(String),
+    InvalidGradientHint(String, String),
+    CouldNotSampleGradient,
+    UnknownEasingFunction(String),
+    UnknownColormap(String),
+    ColormapNotEnoughColors(String, usize),
+    UnknownFormatType(String),
+    InvalidTemplate(String, String),
+    InvalidPipeline(String, String),
+    PipelineWithoutResult,
+    InvalidTokenFile(String),
+    UnresolvedTokenAlias(String),
+    CircularTokenAlias(String),
     DistinctColorCountMustBeLargerThanOne,
     DistinctColorFixedColorsCannotBeMoreThanCount,
+    DistinctColorConstraintsUnsatisfiable,
+    DistinctColorExtendCountMustBePositive,
+    DistinctColorExtendWithPositionalArguments,
+    CouldNotParseHueRange(String),
     ColorPickerExecutionError(String),
     NoColorPickerFound,
     IoError(std::io::Error),
@@ -24,7 +58,20 @@ impl PastelError {
             PastelError::UnknownColorMode(mode) => {
                 format!("Unknown PASTEL_COLOR_MODE value ({})", mode)
             }
-            PastelError::ColorParseError(color) => format!("Could not parse color '{}'", color),
+            PastelError::ColorParseError(color, None) => {
+                format!("Could not parse color '{}'", color)
+            }
+            PastelError::ColorParseError(color, Some(err)) => {
+                format!("Could not parse color: {}", describe_parse_error(color, err))
+            }
+            PastelError::InvalidInputLine(line, column, color, err) => {
+                format!(
+                    "Could not parse color on line {}, column {}: {}",
+                    line,
+                    column,
+                    describe_parse_error(color, err)
+                )
+            }
             PastelError::ColorInvalidUTF8 => "Color input contains invalid UTF8".into(),
             PastelError::CouldNotReadFromStdin => "Could not read color from standard input".into(),
             PastelError::ColorArgRequired => {
@@ -42,12 +89,73 @@ impl PastelError {
             PastelError::GradientColorCountMustBeLargerThanOne => {
                 "The number of color arguments must be larger than one".into()
             }
+            PastelError::CouldNotParseStopPosition(position) => {
+                format!(
+                    "Could not parse color stop position '{}' (expected a number between 0 and 1 or a percentage)",
+                    position
+                )
+            }
+            PastelError::InvalidGradientHint(hint, reason) => {
+                format!("Invalid midpoint hint '{}': {}", hint, reason)
+            }
+            PastelError::CouldNotSampleGradient => {
+                "Could not compute the colors of the gradient".into()
+            }
+            PastelError::UnknownEasingFunction(easing) => {
+                format!("Unknown easing function '{}'", easing)
+            }
+            PastelError::UnknownColormap(name) => {
+                format!(
+                    "Unknown colormap '{}'. See 'pastel colormap --help' for a list of available colormaps.",
+                    name
+                )
+            }
+            PastelError::ColormapNotEnoughColors(name, count) => {
+                format!("The colormap '{}' only has {} colors", name, count)
+            }
+            PastelError::UnknownFormatType(format_type) => {
+                format!(
+                    "Unknown format type '{}'. See 'pastel format --help' for a list of all format types.",
+                    format_type
+                )
+            }
+            PastelError::InvalidTemplate(template, reason) => {
+                format!("Invalid template '{}': {}", template, reason)
+            }
+            PastelError::InvalidPipeline(pipeline, reason) => {
+                format!("Invalid command pipeline '{}': {}", pipeline, reason)
+            }
+            PastelError::PipelineWithoutResult => "The command pipeline did not return a color".into(),
+            PastelError::InvalidTokenFile(err) => format!("Could not read design tokens: {}", err),
+            PastelError::UnresolvedTokenAlias(path) => {
+                format!("Could not resolve design token reference '{{{}}}'", path)
+            }
+            PastelError::CircularTokenAlias(path) => {
+                format!("Circular design token reference '{{{}}}'", path)
+            }
             PastelError::DistinctColorCountMustBeLargerThanOne => {
                 "The number of colors must be larger than one".into()
             }
             PastelError::DistinctColorFixedColorsCannotBeMoreThanCount => {
                 "The number of fixed colors must be smaller than the total number of colors".into()
             }
+            PastelError::DistinctColorConstraintsUnsatisfiable => {
+                "Could not find colors that satisfy the given constraints".into()
+            }
+            PastelError::DistinctColorExtendCountMustBePositive => {
+                "The number of new colors must be larger than zero".into()
+            }
+            PastelError::DistinctColorExtendWithPositionalArguments => {
+                "Positional arguments can not be used with '--extend'. Use '--colors' or \
+                 '--palette' to specify the existing palette."
+                    .into()
+            }
+            PastelError::CouldNotParseHueRange(range) => {
+                format!(
+                    "Could not parse hue range '{}' (expected 'start-end' in degrees)",
+                    range
+                )
+            }
             PastelError::ColorPickerExecutionError(name) => {
                 format!("Error while running color picker '{}'", name)
             }
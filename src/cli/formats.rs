@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::error::{PastelError, Result};
+use crate::utility::similar_colors;
+
+use pastel::ansi::{AnsiColor, Mode};
+use pastel::{Color, Format, LinearRGBA};
+
+/// The value of an output format or template placeholder for a given color.
+pub enum Value {
+    /// A number and its default precision
+    Number(f64, usize),
+    Text(String),
+}
+
+impl Value {
+    fn format(self, precision: Option<usize>) -> String {
+        match self {
+            Value::Number(number, default_precision) => {
+                format!("{:.*}", precision.unwrap_or(default_precision), number)
+            }
+            Value::Text(text) => text,
+        }
+    }
+}
+
+/// How the value of a format is computed.
+pub enum ChannelValue {
+    Color(fn(&Color) -> Value),
+    /// A variable declaration for the color (e.g. `$red: #ff0000;`), given the name of the
+    /// variable. The declarations of a palette are optionally enclosed by a start and end line.
+    Variable {
+        declaration: fn(&str, &Color) -> String,
+        enclosing: Option<(&'static str, &'static str)>,
+    },
+}
+
+/// An output format (`pastel format TYPE`) or template placeholder (`{TYPE}`).
+pub struct Channel {
+    /// The name of the format type, followed by aliases that can be used in templates
+    pub names: &'static [&'static str],
+    /// Whether or not the format can not represent transparency. Transparent colors are
+    /// composited onto the `--background` color for opaque formats.
+    pub opaque: bool,
+    pub value: ChannelValue,
+}
+
+impl Channel {
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// The lines that enclose the formatted colors of a palette, e.g. `colors: {` and `}`.
+    pub fn enclosing(&self) -> Option<(&'static str, &'static str)> {
+        match self.value {
+            ChannelValue::Variable { enclosing, .. } => enclosing,
+            ChannelValue::Color(_) => None,
+        }
+    }
+
+    /// Format the color, after compositing it onto the background for opaque formats. Variable
+    /// names are taken from the closest named color.
+    pub fn format(
+        &self,
+        color: &Color,
+        background: Option<&Color>,
+        precision: Option<usize>,
+    ) -> String {
+        self.format_with_names(color, background, precision, None)
+    }
+
+    /// Like `format`, but the variable names are unique within the palette. Declarations that are
+    /// enclosed by a start and end line (see `enclosing`) are indented.
+    pub fn format_in_palette(
+        &self,
+        color: &Color,
+        background: Option<&Color>,
+        names: &VariableNames,
+    ) -> String {
+        let output = self.format_with_names(color, background, None, Some(names));
+        match self.enclosing() {
+            Some(_) => format!("  {}", output),
+            None => output,
+        }
+    }
+
+    fn format_with_names(
+        &self,
+        color: &Color,
+        background: Option<&Color>,
+        precision: Option<usize>,
+        names: Option<&VariableNames>,
+    ) -> String {
+        let color = match background {
+            Some(background) if self.opaque => background.composite(color),
+            _ => color.clone(),
+        };
+        match self.value {
+            ChannelValue::Color(value) => value(&color).format(precision),
+            ChannelValue::Variable { declaration, .. } => {
+                let name = match names {
+                    Some(names) => names.next(&color),
+                    None => name(&color).into(),
+                };
+                declaration(&name, &color)
+            }
+        }
+    }
+}
+
+/// Unique variable names for the colors of a palette. The names are taken from the closest named
+/// color, with an index suffix for repeated names (`red`, `red-2`, `red-3`).
+#[derive(Default)]
+pub struct VariableNames {
+    counts: Mutex<HashMap<&'static str, usize>>,
+}
+
+impl VariableNames {
+    fn next(&self, color: &Color) -> String {
+        let name = name(color);
+        let mut counts = self.counts.lock().expect("not poisoned");
+        let count = counts.entry(name).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            name.into()
+        } else {
+            format!("{}-{}", name, count)
+        }
+    }
+}
+
+macro_rules! number {
+    ($precision:expr, $f:expr) => {
+        ChannelValue::Color(|c: &Color| Value::Number($f(c), $precision))
+    };
+}
+
+macro_rules! text {
+    ($f:expr) => {
+        ChannelValue::Color(|c: &Color| Value::Text($f(c)))
+    };
+}
+
+macro_rules! variable {
+    ($f:expr) => {
+        ChannelValue::Variable {
+            declaration: $f,
+            enclosing: None,
+        }
+    };
+    ($f:expr, $start:expr, $end:expr) => {
+        ChannelValue::Variable {
+            declaration: $f,
+            enclosing: Some(($start, $end)),
+        }
+    };
+}
+
+fn name(color: &Color) -> &'static str {
+    similar_colors(color)[0].name
+}
+
+fn replace_escape(code: &str) -> String {
+    code.replace('\x1b', "\\x1b")
+}
+
+/// A color resource. Hyphens in the name are replaced, as they are not allowed in Android
+/// resource names.
+fn android_xml(name: &str, color: &Color) -> String {
+    let rgba = color.to_rgba();
+    let hex = if rgba.alpha == 1.0 {
+        format!("#{:06X}", rgba.to_u32())
+    } else {
+        format!("#{:08X}", rgba.to_argb_u32())
+    };
+    format!("<color name=\"{}\">{}</color>", name.replace('-', "_"), hex)
+}
+
+#[rustfmt::skip]
+pub static CHANNELS: &[Channel] = &[
+    Channel { names: &["rgb"], opaque: false, value: text!(|c: &Color| c.to_rgb_string(Format::Spaces)) },
+    Channel { names: &["rgb-float"], opaque: false, value: text!(|c: &Color| c.to_rgb_float_string(Format::Spaces)) },
+    Channel { names: &["red", "r"], opaque: false, value: number!(0, |c: &Color| c.to_rgba().r as f64) },
+    Channel { names: &["green", "g"], opaque: false, value: number!(0, |c: &Color| c.to_rgba().g as f64) },
+    Channel { names: &["blue", "b"], opaque: false, value: number!(0, |c: &Color| c.to_rgba().b as f64) },
+    Channel { names: &["alpha"], opaque: false, value: number!(3, |c: &Color| c.to_rgba().alpha) },
+    Channel { names: &["hex"], opaque: true, value: text!(|c: &Color| c.to_rgb_hex_string(true)) },
+    Channel { names: &["hex8"], opaque: false, value: text!(|c: &Color| c.to_rgb_hex8_string(true)) },
+    Channel { names: &["hex-short"], opaque: true, value: text!(|c: &Color| c.to_rgb_hex_short_string(true)) },
+    Channel { names: &["hex-argb"], opaque: false, value: text!(|c: &Color| format!("0x{:08X}", c.to_argb_u32())) },
+    Channel { names: &["hex-rgba"], opaque: false, value: text!(|c: &Color| format!("0x{:08X}", c.to_rgba_u32())) },
+    Channel { names: &["hsl"], opaque: false, value: text!(|c: &Color| c.to_hsl_string(Format::Spaces)) },
+    Channel { names: &["hsl-hue", "hsl.h"], opaque: false, value: number!(0, |c: &Color| c.to_hsla().h) },
+    Channel { names: &["hsl-saturation", "hsl.s"], opaque: false, value: number!(4, |c: &Color| c.to_hsla().s) },
+    Channel { names: &["hsl-lightness", "hsl.l"], opaque: false, value: number!(4, |c: &Color| c.to_hsla().l) },
+    Channel { names: &["hsv"], opaque: false, value: text!(|c: &Color| c.to_hsv_string(Format::Spaces)) },
+    Channel { names: &["hsv-hue", "hsv.h"], opaque: false, value: number!(0, |c: &Color| c.to_hsva().h) },
+    Channel { names: &["hsv-saturation", "hsv.s"], opaque: false, value: number!(4, |c: &Color| c.to_hsva().s) },
+    Channel { names: &["hsv-value", "hsv.v"], opaque: false, value: number!(4, |c: &Color| c.to_hsva().v) },
+    Channel { names: &["hwb"], opaque: false, value: text!(|c: &Color| c.to_hwb_string(Format::Spaces)) },
+    Channel { names: &["hwb-hue", "hwb.h"], opaque: false, value: number!(0, |c: &Color| c.to_hwba().h) },
+    Channel { names: &["hwb-whiteness", "hwb.w"], opaque: false, value: number!(4, |c: &Color| c.to_hwba().w) },
+    Channel { names: &["hwb-blackness", "hwb.b"], opaque: false, value: number!(4, |c: &Color| c.to_hwba().b) },
+    Channel { names: &["lch"], opaque: false, value: text!(|c: &Color| c.to_lch_string(Format::Spaces)) },
+    Channel { names: &["lch-lightness", "lch.l", "lab.l"], opaque: false, value: number!(2, |c: &Color| c.to_lch().l) },
+    Channel { names: &["lch-chroma", "lch.c"], opaque: false, value: number!(2, |c: &Color| c.to_lch().c) },
+    Channel { names: &["lch-hue", "lch.h"], opaque: false, value: number!(2, |c: &Color| c.to_lch().h) },
+    Channel { names: &["srgb-linear"], opaque: false, value: text!(|c: &Color| LinearRGBA::from(c).to_string()) },
+    Channel { names: &["lab"], opaque: false, value: text!(|c: &Color| c.to_lab_string(Format::Spaces)) },
+    Channel { names: &["lab-a", "lab.a"], opaque: false, value: number!(2, |c: &Color| c.to_lab().a) },
+    Channel { names: &["lab-b", "lab.b"], opaque: false, value: number!(2, |c: &Color| c.to_lab().b) },
+    Channel { names: &["luminance"], opaque: false, value: number!(3, |c: &Color| c.luminance()) },
+    Channel { names: &["brightness"], opaque: false, value: number!(3, |c: &Color| c.brightness()) },
+    Channel { names: &["ansi-8bit"], opaque: true, value: text!(|c: &Color| replace_escape(&c.to_ansi_sequence(Mode::Ansi8Bit))) },
+    Channel { names: &["ansi-24bit"], opaque: true, value: text!(|c: &Color| replace_escape(&c.to_ansi_sequence(Mode::TrueColor))) },
+    Channel { names: &["ansi-8bit-escapecode"], opaque: true, value: text!(|c: &Color| c.to_ansi_sequence(Mode::Ansi8Bit)) },
+    Channel { names: &["ansi-24bit-escapecode"], opaque: true, value: text!(|c: &Color| c.to_ansi_sequence(Mode::TrueColor)) },
+    Channel { names: &["cmyk"], opaque: true, value: text!(|c: &Color| c.to_cmyk_string(Format::Spaces)) },
+    Channel { names: &["name"], opaque: true, value: text!(|c: &Color| name(c).to_owned()) },
+    Channel { names: &["uicolor"], opaque: false, value: text!(|c: &Color| {
+        let rgba = c.to_rgba_float();
+        format!("UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.3})", rgba.r, rgba.g, rgba.b, rgba.alpha)
+    }) },
+    Channel { names: &["swiftui"], opaque: false, value: text!(|c: &Color| {
+        let rgba = c.to_rgba_float();
+        format!("Color(red: {:.3}, green: {:.3}, blue: {:.3}, opacity: {:.3})", rgba.r, rgba.g, rgba.b, rgba.alpha)
+    }) },
+    Channel { names: &["compose"], opaque: false, value: text!(|c: &Color| format!("Color(0x{:08X})", c.to_argb_u32())) },
+    Channel { names: &["android-xml"], opaque: false, value: variable!(android_xml) },
+    Channel { names: &["css-var"], opaque: false, value: variable!(|name: &str, c: &Color| format!("--{}: {};", name, c.to_rgb_hex_string(true))) },
+    Channel { names: &["scss"], opaque: false, value: variable!(|name: &str, c: &Color| format!("${}: {};", name, c.to_rgb_hex_string(true))) },
+    Channel { names: &["less"], opaque: false, value: variable!(|name: &str, c: &Color| format!("@{}: {};", name, c.to_rgb_hex_string(true))) },
+    Channel { names: &["tailwind"], opaque: false, value: variable!(|name: &str, c: &Color| format!("'{}': '{}',", name, c.to_rgb_hex_string(true)), "colors: {", "}") },
+];
+
+pub fn channel_by_name(name: &str) -> Option<&'static Channel> {
+    let name = name.to_lowercase();
+    CHANNELS
+        .iter()
+        .find(|channel| channel.names.contains(&name.as_str()))
+}
+
+enum Segment {
+    Literal(String),
+    Placeholder(&'static Channel, Option<usize>),
+}
+
+/// A parsed output template like `{r},{g},{b} /* {name} */`. Placeholders are format types or
+/// channel names with an optional precision (`{lch.c:.1}`). Literal braces are written as `{{`
+/// and `}}`.
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |reason: &str| PastelError::InvalidTemplate(template.into(), reason.into());
+
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(invalid("unmatched '}'")),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(invalid("unterminated placeholder")),
+                        }
+                    }
+
+                    let (name, precision) = match placeholder.split_once(':') {
+                        Some((name, spec)) => {
+                            let precision = spec
+                                .strip_prefix('.')
+                                .and_then(|p| p.parse::<usize>().ok())
+                                .ok_or_else(|| {
+                                    invalid(&format!("invalid format specification '{}'", spec))
+                                })?;
+                            (name, Some(precision))
+                        }
+                        None => (placeholder.as_str(), None),
+                    };
+
+                    let channel = channel_by_name(name.trim())
+                        .ok_or_else(|| invalid(&format!("unknown placeholder '{{{}}}'", name)))?;
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(channel, precision));
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Template { segments })
+    }
+
+    pub fn render(&self, color: &Color, background: Option<&Color>) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Placeholder(channel, precision) => {
+                    channel.format(color, background, *precision)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let color = Color::from_rgba(255, 0, 119, 0.5);
+        let render = |template: &str| Template::parse(template).unwrap().render(&color, None);
+
+        assert_eq!(
+            "255,0,119 /* deeppink */",
+            render("{r},{g},{b} /* {name} */")
+        );
+        assert_eq!("{#ff007780}", render("{{{hex}}}"));
+        assert_eq!("0.5 0.500", render("{alpha:.1} {alpha}"));
+        assert_eq!("332", render("{hsl.h}"));
+
+        let background = Color::white();
+        assert_eq!(
+            "#ff7fbb 0.500",
+            Template::parse("{hex} {alpha}")
+                .unwrap()
+                .render(&color, Some(&background))
+        );
+    }
+
+    #[test]
+    fn invalid_templates() {
+        assert!(Template::parse("{r").is_err());
+        assert!(Template::parse("r}").is_err());
+        assert!(Template::parse("{foo}").is_err());
+        assert!(Template::parse("{r:3}").is_err());
+    }
+}
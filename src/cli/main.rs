@@ -1,4 +1,5 @@
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use atty::Stream;
 
@@ -9,12 +10,13 @@ mod colorspace;
 mod commands;
 mod config;
 mod error;
+mod formats;
 mod hdcanvas;
 mod output;
 mod utility;
 
-use commands::Command;
-use config::Config;
+use commands::{ColorArgIterator, Command, PrintSpectrum};
+use config::{ColorLocation, Config, OnError};
 use error::{PastelError, Result};
 
 use pastel::ansi::{self, Brush, Mode};
@@ -22,6 +24,9 @@ use pastel::Color;
 
 type ExitCode = i32;
 
+/// The exit code if some lines of the input could not be parsed (see `--on-error`)
+const EXIT_CODE_INVALID_INPUT: ExitCode = 3;
+
 fn write_stderr(c: Color, title: &str, message: &str) {
     writeln!(
         io::stderr(),
@@ -98,15 +103,62 @@ fn run() -> Result<ExitCode> {
         }
     };
 
-    let config = Config {
+    let color_location = match (
+        global_matches.value_of("field"),
+        global_matches.value_of("json-path"),
+    ) {
+        (Some(field), _) => {
+            let n = field
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| PastelError::CouldNotParseNumber(field.into()))?;
+            let delimiter = global_matches.value_of("delimiter").map(|d| match d {
+                "\\t" => "\t".to_string(),
+                d => d.to_string(),
+            });
+            ColorLocation::Field(n, delimiter)
+        }
+        (None, Some(path)) => ColorLocation::JsonPath(path.into()),
+        (None, None) => ColorLocation::Line,
+    };
+
+    let jobs = match global_matches.value_of("jobs") {
+        Some(jobs) => jobs
+            .parse::<usize>()
+            .map_err(|_| PastelError::CouldNotParseNumber(jobs.into()))?,
+        None => 1,
+    };
+
+    let mut config = Config {
         padding: 2,
         colorpicker_width: 48,
         colorcheck_width: 8,
         interactive_mode,
         brush: Brush::from_mode(color_mode),
         colorpicker: global_matches.value_of("color-picker"),
+        background: None,
+        on_error: match global_matches
+            .value_of("on-error")
+            .expect("required argument")
+        {
+            "fail" => OnError::Fail,
+            "skip" => OnError::Skip,
+            "warn" => OnError::Warn,
+            "passthrough" => OnError::Passthrough,
+            _ => unreachable!("Unknown --on-error argument"),
+        },
+        color_location,
+        jobs,
+        invalid_input: AtomicBool::new(false),
     };
 
+    if let Some(background) = global_matches.value_of("background") {
+        let background =
+            ColorArgIterator::from_color_arg(&config, background, &mut PrintSpectrum::Yes)?;
+        config.background = Some(background);
+    }
+
     if let Some((subcommand, matches)) = global_matches.subcommand() {
         let command = Command::from_string(subcommand);
         command.execute(matches, &config)?;
@@ -114,6 +166,10 @@ fn run() -> Result<ExitCode> {
         unreachable!("Subcommand is required");
     }
 
+    if config.invalid_input.load(Ordering::Relaxed) {
+        return Ok(EXIT_CODE_INVALID_INPUT);
+    }
+
     Ok(0)
 }
 
@@ -12,16 +12,32 @@ use pastel::Format;
 pub struct Output<'a> {
     pub handle: &'a mut dyn Write,
     colors_shown: usize,
+    collected: Option<Vec<Color>>,
 }
 
 impl Output<'_> {
-    pub fn new(handle: &mut dyn Write) -> Output {
+    pub fn new(handle: &mut dyn Write) -> Output<'_> {
         Output {
             handle,
             colors_shown: 0,
+            collected: None,
         }
     }
 
+    /// An output that collects the shown colors instead of printing them.
+    pub fn collecting(handle: &mut dyn Write) -> Output<'_> {
+        Output {
+            handle,
+            colors_shown: 0,
+            collected: Some(vec![]),
+        }
+    }
+
+    /// The colors that have been shown on a collecting output.
+    pub fn into_colors(self) -> Vec<Color> {
+        self.collected.unwrap_or_default()
+    }
+
     pub fn show_color_tty(&mut self, config: &Config, color: &Color) -> Result<()> {
         let checkerboard_size: usize = 16;
         let color_panel_size: usize = 12;
@@ -36,14 +52,24 @@ impl Output<'_> {
         let text_position_y: usize = 0;
 
         let mut canvas = Canvas::new(checkerboard_size, 60, config.brush);
-        canvas.draw_checkerboard(
-            checkerboard_position_y,
-            checkerboard_position_x,
-            checkerboard_size,
-            checkerboard_size,
-            &Color::graytone(0.94),
-            &Color::graytone(0.71),
-        );
+        // transparent colors are shown on the `--background` color, if given
+        match &config.background {
+            Some(background) => canvas.draw_rect(
+                checkerboard_position_y,
+                checkerboard_position_x,
+                checkerboard_size,
+                checkerboard_size,
+                background,
+            ),
+            None => canvas.draw_checkerboard(
+                checkerboard_position_y,
+                checkerboard_position_x,
+                checkerboard_size,
+                checkerboard_size,
+                &Color::graytone(0.94),
+                &Color::graytone(0.71),
+            ),
+        }
         canvas.draw_rect(
             color_panel_position_y,
             color_panel_position_x,
@@ -103,7 +129,9 @@ impl Output<'_> {
     }
 
     pub fn show_color(&mut self, config: &Config, color: &Color) -> Result<()> {
-        if config.interactive_mode {
+        if let Some(collected) = &mut self.collected {
+            collected.push(color.clone());
+        } else if config.interactive_mode {
             if self.colors_shown < 1 {
                 writeln!(self.handle)?
             };
@@ -2,13 +2,13 @@ use std::fmt;
 
 use nom::{
     bytes::complete::tag_no_case,
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::{all_consuming, opt},
-    IResult,
+    error::context,
 };
 
 use crate::{
-    parser::{modern_alpha, number_or_percentage},
+    parser::{closing_parenthesis, component, modern_alpha, number_or_percentage, IResult},
     rgb::RGBA,
     types::Scalar,
     Color, Format,
@@ -93,6 +93,11 @@ impl CMYK {
     }
 }
 
+const CYAN: &str = "expected cyan (a number or a percentage)";
+const MAGENTA: &str = "expected magenta (a number or a percentage)";
+const YELLOW: &str = "expected yellow (a number or a percentage)";
+const BLACK: &str = "expected black (a number or a percentage)";
+
 pub(crate) fn parse_cmyk_color(input: &str) -> IResult<&str, Color> {
     all_consuming(parse_css_device_cmyk)(input.trim())
 }
@@ -105,17 +110,14 @@ fn parse_css_device_cmyk(input: &str) -> IResult<&str, Color> {
     let (input, _) = opt(tag_no_case("device-"))(input)?;
     let (input, _) = tag_no_case("cmyk(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, c) = number_or_percentage(input, 1.0)?;
-    let (input, _) = space1(input)?;
-    let (input, m) = number_or_percentage(input, 1.0)?;
-    let (input, _) = space1(input)?;
-    let (input, y) = number_or_percentage(input, 1.0)?;
-    let (input, _) = space1(input)?;
-    let (input, k) = number_or_percentage(input, 1.0)?;
+    let value = |input| number_or_percentage(input, 1.0);
+    let (input, c) = context(CYAN, value)(input)?;
+    let (input, m) = component(MAGENTA, space1, value)(input)?;
+    let (input, y) = component(YELLOW, space1, value)(input)?;
+    let (input, k) = component(BLACK, space1, value)(input)?;
     // accept alpha component for compatibility, but not currently supported for CMYK
     let (input, _alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_cmyk(c, m, y, k);
 
@@ -1,4 +1,5 @@
-use crate::{Color, Fraction};
+use crate::types::Scalar;
+use crate::{Color, Easing, Fraction};
 
 /// The representation of a color stop for a `ColorScale`.
 /// The position defines where the color is placed from left (0.0) to right (1.0).
@@ -14,6 +15,9 @@ struct ColorStop {
 #[derive(Debug, Clone)]
 pub struct ColorScale {
     color_stops: Vec<ColorStop>,
+    hints: Vec<Fraction>,
+    easing: Easing,
+    correct_lightness: bool,
 }
 
 impl ColorScale {
@@ -21,9 +25,37 @@ impl ColorScale {
     pub fn empty() -> Self {
         Self {
             color_stops: Vec::new(),
+            hints: Vec::new(),
+            easing: Easing::Linear,
+            correct_lightness: false,
         }
     }
 
+    /// Set the easing function that is applied between each pair of neighboring color stops.
+    pub fn set_easing(&mut self, easing: Easing) -> &mut Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Re-parameterize the positions such that the Lab lightness changes linearly from the first
+    /// to the last color stop (similar to `correctLightness` in chroma.js). This makes sequential
+    /// color scales perceptually more uniform. The correction works best if the lightness of the
+    /// uncorrected scale is monotonic.
+    pub fn set_correct_lightness(&mut self, correct_lightness: bool) -> &mut Self {
+        self.correct_lightness = correct_lightness;
+        self
+    }
+
+    /// Add a midpoint hint at the given position. Between the two surrounding color stops, the
+    /// interpolation is re-parameterized such that the halfway color appears at the hint (as in
+    /// CSS gradients). Hints that do not lie strictly between two color stops are ignored.
+    ///
+    /// See: <https://www.w3.org/TR/css-images-4/#color-transition-hint>
+    pub fn add_hint(&mut self, position: Fraction) -> &mut Self {
+        self.hints.push(position);
+        self
+    }
+
     /// Add a `Color` at the given position.
     pub fn add_stop(&mut self, color: Color, position: Fraction) -> &mut Self {
         #![allow(clippy::float_cmp)]
@@ -35,23 +67,31 @@ impl ColorScale {
         match same_position {
             Some(color_stop) => color_stop.color = color,
             None => {
-                let next_index = self
-                    .color_stops
-                    .iter()
-                    .position(|c| position.value() < c.position.value());
+                self.add_hard_stop(color, position);
+            }
+        };
 
-                let index = next_index.unwrap_or(self.color_stops.len());
+        self
+    }
 
-                let color_stop = ColorStop { color, position };
+    /// Add a `Color` at the given position. Unlike `add_stop`, an existing color stop at this
+    /// position is kept and the new stop is placed after it, which creates a hard transition
+    /// between the two colors (as in CSS gradients).
+    pub fn add_hard_stop(&mut self, color: Color, position: Fraction) -> &mut Self {
+        let index = self
+            .color_stops
+            .iter()
+            .position(|c| position.value() < c.position.value())
+            .unwrap_or(self.color_stops.len());
 
-                self.color_stops.insert(index, color_stop);
-            }
-        };
+        self.color_stops
+            .insert(index, ColorStop { color, position });
 
         self
     }
 
-    /// Get the color at the given position using the mixing function.
+    /// Get the color at the given position using the mixing function. Midpoint hints and the
+    /// easing function are applied to the position between the two neighboring color stops.
     ///
     /// Note:
     /// - No color is returned if position isn't between two color stops or the `ColorScale` is empty.
@@ -60,40 +100,268 @@ impl ColorScale {
         position: Fraction,
         mix: &dyn Fn(&Color, &Color, Fraction) -> Color,
     ) -> Option<Color> {
-        if self.color_stops.len() < 2 {
-            return None;
-        }
+        let sample = |position: Fraction| -> Option<Color> {
+            let (index, local_position) = self.segment(position)?;
+
+            let left_stop = &self.color_stops[index];
+            let right_stop = &self.color_stops[index + 1];
+
+            Some(mix(&left_stop.color, &right_stop.color, local_position))
+        };
+
+        sample(self.lightness_corrected(position, &sample)?)
+    }
 
-        let left_stop = self
+    /// Get the color at the given position by interpolating all color stops with a smooth curve.
+    /// The interpolation is performed separately for each channel in the Lab color space, with
+    /// premultiplied alpha.
+    /// Midpoint hints and the easing function are applied like in `sample`.
+    ///
+    /// Note:
+    /// - No color is returned if position isn't between two color stops or the `ColorScale` is empty.
+    pub fn sample_spline(&self, position: Fraction, spline: Spline) -> Option<Color> {
+        let sample = |position: Fraction| self.spline(position, spline);
+
+        sample(self.lightness_corrected(position, &sample)?)
+    }
+
+    fn spline(&self, position: Fraction, spline: Spline) -> Option<Color> {
+        let (index, local_position) = self.segment(position)?;
+        let t = local_position.value();
+
+        let points: Vec<[Scalar; 4]> = self
             .color_stops
             .iter()
-            .rev()
-            .find(|c| position.value() >= c.position.value());
+            .map(|stop| {
+                let lab = stop.color.to_lab();
+                [
+                    lab.l * lab.alpha,
+                    lab.a * lab.alpha,
+                    lab.b * lab.alpha,
+                    lab.alpha,
+                ]
+            })
+            .collect();
+
+        let last = points.len() - 1;
+        let point = |i: isize| -> [Scalar; 4] {
+            // reflect the end points to get a tangent at the first and last color stop
+            if i < 0 {
+                channels(|c| 2.0 * points[0][c] - points[1][c])
+            } else if i as usize > last {
+                channels(|c| 2.0 * points[last][c] - points[last - 1][c])
+            } else {
+                points[i as usize]
+            }
+        };
+
+        let i = index as isize;
+        let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+
+        let value: [Scalar; 4] = match spline {
+            Spline::Basis => channels(|c| basis(p0[c], p1[c], p2[c], p3[c], t)),
+            Spline::CatmullRom => channels(|c| catmull_rom(p0[c], p1[c], p2[c], p3[c], t)),
+            Spline::MonotoneCubic => {
+                let positions: Vec<Scalar> = self
+                    .color_stops
+                    .iter()
+                    .map(|stop| stop.position.value())
+                    .collect();
+
+                channels(|c| {
+                    let values: Vec<Scalar> = points.iter().map(|p| p[c]).collect();
+                    monotone_cubic(&positions, &values, index, t)
+                })
+            }
+        };
+
+        let [l, a, b, alpha] = value;
+        let alpha = alpha.clamp(0.0, 1.0);
+        if alpha == 0.0 {
+            Some(Color::from_lab(0.0, 0.0, 0.0, 0.0))
+        } else {
+            Some(Color::from_lab(l / alpha, a / alpha, b / alpha, alpha))
+        }
+    }
+
+    /// Find the position at which the Lab lightness of the color scale equals the linearly
+    /// interpolated lightness of the end points. The position is returned unchanged if lightness
+    /// correction is disabled.
+    fn lightness_corrected(
+        &self,
+        position: Fraction,
+        sample: &dyn Fn(Fraction) -> Option<Color>,
+    ) -> Option<Fraction> {
+        const MAX_ITERATIONS: usize = 30;
+        const EPSILON: Scalar = 0.01;
+
+        if !self.correct_lightness {
+            return Some(position);
+        }
+
+        let first = self.color_stops.first()?.position;
+        let last = self.color_stops.last()?.position;
+
+        let lightness = |p: Fraction| sample(p).map(|c| c.to_lab().l);
+        let first_lightness = lightness(first)?;
+        let last_lightness = lightness(last)?;
+
+        let relative_position = (position.value() - first.value()) / (last.value() - first.value());
+        let target = first_lightness + (last_lightness - first_lightness) * relative_position;
+        let increasing = last_lightness >= first_lightness;
+
+        let (mut lower, mut upper) = (first.value(), last.value());
+        let mut corrected = position;
+        for _ in 0..MAX_ITERATIONS {
+            let difference = lightness(corrected)? - target;
+            if difference.abs() < EPSILON {
+                break;
+            }
+
+            if (difference < 0.0) == increasing {
+                lower = corrected.value();
+            } else {
+                upper = corrected.value();
+            }
+            corrected = Fraction::from((lower + upper) / 2.0);
+        }
+
+        Some(corrected)
+    }
+
+    /// Find the pair of color stops around the given position. Returns the index of the left
+    /// color stop and the position relative to the two stops, after applying midpoint hints and
+    /// the easing function.
+    fn segment(&self, position: Fraction) -> Option<(usize, Fraction)> {
+        let count = self.color_stops.len();
+        if count < 2 {
+            return None;
+        }
+
+        let first = self.color_stops[0].position.value();
+        let last = self.color_stops[count - 1].position.value();
+        if position.value() < first || position.value() > last {
+            return None;
+        }
 
-        let right_stop = self
+        // At a position with several color stops, the last one is used
+        let index = self
             .color_stops
             .iter()
-            .find(|c| position.value() <= c.position.value());
+            .rposition(|c| position.value() >= c.position.value())
+            .unwrap_or(0)
+            .min(count - 2);
 
-        match (left_stop, right_stop) {
-            (Some(left_stop), Some(right_stop)) => {
-                let diff_color_stops = right_stop.position.value() - left_stop.position.value();
-                let diff_position = position.value() - left_stop.position.value();
-                let local_position = Fraction::from(diff_position / diff_color_stops);
+        let left = self.color_stops[index].position.value();
+        let right = self.color_stops[index + 1].position.value();
 
-                let color = mix(&left_stop.color, &right_stop.color, local_position);
+        #[allow(clippy::float_cmp)]
+        if left == right {
+            return Some((index, Fraction::from(1.0)));
+        }
 
-                Some(color)
-            }
-            _ => None,
+        let mut local_position = Fraction::from((position.value() - left) / (right - left));
+
+        let hint = self
+            .hints
+            .iter()
+            .find(|h| h.value() > left && h.value() < right);
+        if let Some(hint) = hint {
+            let local_hint = (hint.value() - left) / (right - left);
+            local_position = Fraction::from(
+                local_position
+                    .value()
+                    .powf(Scalar::ln(0.5) / Scalar::ln(local_hint)),
+            );
         }
+
+        Some((index, self.easing.apply(local_position)))
     }
 }
 
+/// Smooth interpolation methods for `ColorScale::sample_spline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spline {
+    /// Uniform cubic B-spline. The curve is very smooth but only passes through the first and the
+    /// last color stop. The inner color stops act as control points.
+    Basis,
+
+    /// Catmull-Rom spline. The curve passes through all color stops, but may overshoot.
+    CatmullRom,
+
+    /// Monotone cubic interpolation (Steffen's method). The curve passes through all color
+    /// stops and never overshoots, i.e. each channel is monotonic between two color stops.
+    MonotoneCubic,
+}
+
+/// Compute the four channels (L, a, b, alpha) of an interpolated color.
+fn channels(f: impl Fn(usize) -> Scalar) -> [Scalar; 4] {
+    [f(0), f(1), f(2), f(3)]
+}
+
+fn basis(p0: Scalar, p1: Scalar, p2: Scalar, p3: Scalar, t: Scalar) -> Scalar {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    ((1.0 - 3.0 * t + 3.0 * t2 - t3) * p0
+        + (4.0 - 6.0 * t2 + 3.0 * t3) * p1
+        + (1.0 + 3.0 * t + 3.0 * t2 - 3.0 * t3) * p2
+        + t3 * p3)
+        / 6.0
+}
+
+fn catmull_rom(p0: Scalar, p1: Scalar, p2: Scalar, p3: Scalar, t: Scalar) -> Scalar {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Evaluate a monotone cubic Hermite spline through the points `(xs[i], ys[i])` between the
+/// points `index` and `index + 1` at the relative position `t`.
+///
+/// See: Steffen, M. (1990). A simple method for monotonic interpolation in one dimension.
+fn monotone_cubic(xs: &[Scalar], ys: &[Scalar], index: usize, t: Scalar) -> Scalar {
+    // hard transitions (two color stops at the same position) do not contribute to the slope
+    let secant = |i: usize| {
+        let h = xs[i + 1] - xs[i];
+        if h > 0.0 {
+            (ys[i + 1] - ys[i]) / h
+        } else {
+            0.0
+        }
+    };
+
+    let slope = |i: usize| -> Scalar {
+        if i == 0 {
+            secant(0)
+        } else if i == xs.len() - 1 {
+            secant(i - 1)
+        } else {
+            let (s0, s1) = (secant(i - 1), secant(i));
+            let (h0, h1) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+            let p = (s0 * h1 + s1 * h0) / (h0 + h1);
+            (s0.signum() + s1.signum()) * s0.abs().min(s1.abs()).min(0.5 * p.abs())
+        }
+    };
+
+    let h = xs[index + 1] - xs[index];
+    let (m0, m1) = (slope(index), slope(index + 1));
+
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (2.0 * t3 - 3.0 * t2 + 1.0) * ys[index]
+        + (t3 - 2.0 * t2 + t) * h * m0
+        + (-2.0 * t3 + 3.0 * t2) * ys[index + 1]
+        + (t3 - t2) * h * m1
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Lab;
+    use approx::assert_relative_eq;
 
     #[test]
     fn color_scale_add_preserves_ordering() {
@@ -104,7 +372,10 @@ mod tests {
             .add_stop(Color::gray(), Fraction::from(0.0))
             .add_stop(Color::blue(), Fraction::from(1.0));
 
-        assert_eq!(color_scale.color_stops.get(0).unwrap().color, Color::gray());
+        assert_eq!(
+            color_scale.color_stops.first().unwrap().color,
+            Color::gray()
+        );
         assert_eq!(color_scale.color_stops.get(1).unwrap().color, Color::red());
         assert_eq!(color_scale.color_stops.get(2).unwrap().color, Color::blue());
     }
@@ -148,8 +419,37 @@ mod tests {
         let sample_blue = color_scale.sample(Fraction::from(0.0), &mix).unwrap();
         let sample_white = color_scale.sample(Fraction::from(1.0), &mix).unwrap();
 
+        let sample_middle = color_scale.sample(Fraction::from(0.5), &mix).unwrap();
+
         assert_eq!(sample_blue, Color::blue());
         assert_eq!(sample_white, Color::white());
+        assert_eq!(
+            sample_middle,
+            mix(&Color::blue(), &Color::white(), Fraction::from(0.5))
+        );
+    }
+
+    #[test]
+    fn color_scale_sample_hard_stops() {
+        let mix = Color::mix::<Lab>;
+
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_hard_stop(Color::red(), Fraction::from(0.0))
+            .add_hard_stop(Color::red(), Fraction::from(0.5))
+            .add_hard_stop(Color::blue(), Fraction::from(0.5))
+            .add_hard_stop(Color::blue(), Fraction::from(1.0));
+
+        let sample = |position| color_scale.sample(Fraction::from(position), &mix).unwrap();
+        assert_eq!(sample(0.25), Color::red());
+        assert_eq!(sample(0.5), Color::blue());
+        assert_eq!(sample(0.75), Color::blue());
+
+        for spline in [Spline::Basis, Spline::CatmullRom, Spline::MonotoneCubic] {
+            let color = color_scale.sample_spline(Fraction::from(0.75), spline);
+            assert!(color.is_some());
+        }
     }
 
     #[test]
@@ -197,4 +497,135 @@ mod tests {
         assert_eq!(sample_red_green, mix_red_green);
         assert_eq!(sample_green_blue, mix_green_blue);
     }
+
+    #[test]
+    fn color_scale_sample_hint() {
+        let mix = Color::mix::<Lab>;
+
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_stop(Color::black(), Fraction::from(0.0))
+            .add_stop(Color::white(), Fraction::from(0.5))
+            .add_stop(Color::red(), Fraction::from(1.0))
+            .add_hint(Fraction::from(0.1));
+
+        let sample_hint = color_scale.sample(Fraction::from(0.1), &mix).unwrap();
+        let mix_halfway = mix(&Color::black(), &Color::white(), Fraction::from(0.5));
+        assert_eq!(sample_hint, mix_halfway);
+
+        // the second segment is not affected by the hint
+        let sample = color_scale.sample(Fraction::from(0.75), &mix).unwrap();
+        let mix_halfway = mix(&Color::white(), &Color::red(), Fraction::from(0.5));
+        assert_eq!(sample, mix_halfway);
+    }
+
+    #[test]
+    fn color_scale_sample_easing() {
+        let mix = Color::mix::<Lab>;
+
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_stop(Color::black(), Fraction::from(0.0))
+            .add_stop(Color::white(), Fraction::from(0.5))
+            .add_stop(Color::red(), Fraction::from(1.0))
+            .set_easing(Easing::Smoothstep);
+
+        let sample = color_scale.sample(Fraction::from(0.125), &mix).unwrap();
+        let expected = mix(&Color::black(), &Color::white(), Fraction::from(0.15625));
+        assert_eq!(sample, expected);
+
+        let sample = color_scale.sample(Fraction::from(0.625), &mix).unwrap();
+        let expected = mix(&Color::white(), &Color::red(), Fraction::from(0.15625));
+        assert_eq!(sample, expected);
+    }
+
+    #[test]
+    fn color_scale_sample_spline_end_points() {
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_stop(Color::red(), Fraction::from(0.0))
+            .add_stop(Color::green(), Fraction::from(0.3))
+            .add_stop(Color::blue(), Fraction::from(1.0));
+
+        for spline in [Spline::Basis, Spline::CatmullRom, Spline::MonotoneCubic] {
+            let start = color_scale.sample_spline(Fraction::from(0.0), spline);
+            let end = color_scale.sample_spline(Fraction::from(1.0), spline);
+
+            assert_eq!(start, Some(Color::red()));
+            assert_eq!(end, Some(Color::blue()));
+        }
+
+        // Interpolating splines pass through all color stops
+        for spline in [Spline::CatmullRom, Spline::MonotoneCubic] {
+            let inner = color_scale.sample_spline(Fraction::from(0.3), spline);
+            assert_eq!(inner, Some(Color::green()));
+        }
+
+        assert_eq!(
+            ColorScale::empty().sample_spline(Fraction::from(0.5), Spline::Basis),
+            None
+        );
+    }
+
+    #[test]
+    fn color_scale_sample_spline_monotone() {
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_stop(Color::black(), Fraction::from(0.0))
+            .add_stop(Color::gray(), Fraction::from(0.5))
+            .add_stop(Color::gray(), Fraction::from(0.9))
+            .add_stop(Color::white(), Fraction::from(1.0));
+
+        let lightness = |spline: Spline| -> Vec<Scalar> {
+            (0..=100)
+                .map(|i| {
+                    color_scale
+                        .sample_spline(Fraction::from(i as Scalar / 100.0), spline)
+                        .unwrap()
+                        .to_lab()
+                        .l
+                })
+                .collect()
+        };
+
+        let monotone = lightness(Spline::MonotoneCubic);
+        assert!(monotone.windows(2).all(|w| w[1] >= w[0] - 1e-6));
+
+        // Catmull-Rom overshoots when the curve flattens out
+        let catmull_rom = lightness(Spline::CatmullRom);
+        assert!(catmull_rom.windows(2).any(|w| w[1] < w[0] - 1e-6));
+    }
+
+    #[test]
+    fn color_scale_correct_lightness() {
+        let mix = Color::mix::<Lab>;
+
+        let mut color_scale = ColorScale::empty();
+
+        color_scale
+            .add_stop(Color::black(), Fraction::from(0.0))
+            .add_stop(Color::yellow(), Fraction::from(0.3))
+            .add_stop(Color::white(), Fraction::from(1.0))
+            .set_correct_lightness(true);
+
+        let black_lightness = Color::black().to_lab().l;
+        let white_lightness = Color::white().to_lab().l;
+
+        for i in 0..=10 {
+            let position = i as Scalar / 10.0;
+            let expected = black_lightness + (white_lightness - black_lightness) * position;
+
+            let color = color_scale.sample(Fraction::from(position), &mix).unwrap();
+            assert_relative_eq!(color.to_lab().l, expected, epsilon = 0.5);
+
+            let color = color_scale
+                .sample_spline(Fraction::from(position), Spline::MonotoneCubic)
+                .unwrap();
+            assert_relative_eq!(color.to_lab().l, expected, epsilon = 0.5);
+        }
+    }
 }
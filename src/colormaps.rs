@@ -0,0 +1,437 @@
+//! Predefined color scales for data visualization: the perceptually uniform colormaps from
+//! matplotlib (viridis, inferno, magma, plasma, cividis), Google's turbo and the ColorBrewer
+//! sets by Cynthia Brewer.
+//!
+//! See: <https://bids.github.io/colormap/>, <https://colorbrewer2.org/>
+
+use crate::{Color, ColorScale, Fraction};
+
+/// The intended use of a colormap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColormapKind {
+    /// Ordered data from low to high values
+    Sequential,
+
+    /// Ordered data with a critical mid-point (e.g. zero)
+    Diverging,
+
+    /// Categorical data without any ordering. The colors are not meant to be interpolated.
+    Qualitative,
+}
+
+/// A predefined list of colors.
+#[derive(Debug, Clone)]
+pub struct Colormap {
+    pub name: &'static str,
+    pub kind: ColormapKind,
+    colors: &'static [u32],
+}
+
+impl Colormap {
+    /// The colors that define this colormap.
+    pub fn colors(&self) -> Vec<Color> {
+        self.colors
+            .iter()
+            .map(|&rgb| Color::from_rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+            .collect()
+    }
+
+    /// A `ColorScale` with the colors of this colormap as evenly spaced color stops.
+    pub fn color_scale(&self) -> ColorScale {
+        let colors = self.colors();
+        let last = (colors.len() - 1) as f64;
+
+        let mut color_scale = ColorScale::empty();
+        for (i, color) in colors.into_iter().enumerate() {
+            color_scale.add_stop(color, Fraction::from(i as f64 / last));
+        }
+        color_scale
+    }
+}
+
+const fn colormap(name: &'static str, kind: ColormapKind, colors: &'static [u32]) -> Colormap {
+    Colormap { name, kind, colors }
+}
+
+use ColormapKind::*;
+
+/// All available colormaps.
+pub static COLORMAPS: &[Colormap] = &[
+    // matplotlib
+    colormap(
+        "viridis",
+        Sequential,
+        &[
+            0x440154, 0x482475, 0x414487, 0x355f8d, 0x2a788e, 0x21918c, 0x22a884, 0x44bf70,
+            0x7ad151, 0xbddf26, 0xfde725,
+        ],
+    ),
+    colormap(
+        "inferno",
+        Sequential,
+        &[
+            0x000004, 0x160b39, 0x420a68, 0x6a176e, 0x932667, 0xbc3754, 0xdd513a, 0xf37819,
+            0xfca50a, 0xf6d746, 0xfcffa4,
+        ],
+    ),
+    colormap(
+        "magma",
+        Sequential,
+        &[
+            0x000004, 0x140e36, 0x3b0f70, 0x641a80, 0x8c2981, 0xb73779, 0xde4968, 0xf7705c,
+            0xfe9f6d, 0xfecf92, 0xfcfdbf,
+        ],
+    ),
+    colormap(
+        "plasma",
+        Sequential,
+        &[
+            0x0d0887, 0x41049d, 0x6a00a8, 0x8f0da4, 0xb12a90, 0xcc4778, 0xe16462, 0xf2844b,
+            0xfca636, 0xfcce25, 0xf0f921,
+        ],
+    ),
+    colormap(
+        "cividis",
+        Sequential,
+        &[
+            0x00204d, 0x00336f, 0x39486b, 0x575c6d, 0x707173, 0x8a8779, 0xa69d75, 0xc4b56c,
+            0xe4cf5b, 0xffea46,
+        ],
+    ),
+    // polynomial approximation of turbo, as in d3-scale-chromatic
+    colormap(
+        "turbo",
+        Sequential,
+        &[
+            0x23171b, 0x493eae, 0x4569ee, 0x3295f7, 0x26bce1, 0x29dcbc, 0x3ff393, 0x65fd6e,
+            0x95fb51, 0xc5ec3c, 0xecd12e, 0xffad24, 0xff821d, 0xf05616, 0xcb2f0d, 0xa31302,
+            0x900c00,
+        ],
+    ),
+    // ColorBrewer, sequential
+    colormap(
+        "Blues",
+        Sequential,
+        &[
+            0xf7fbff, 0xdeebf7, 0xc6dbef, 0x9ecae1, 0x6baed6, 0x4292c6, 0x2171b5, 0x08519c,
+            0x08306b,
+        ],
+    ),
+    colormap(
+        "BuGn",
+        Sequential,
+        &[
+            0xf7fcfd, 0xe5f5f9, 0xccece6, 0x99d8c9, 0x66c2a4, 0x41ae76, 0x238b45, 0x006d2c,
+            0x00441b,
+        ],
+    ),
+    colormap(
+        "BuPu",
+        Sequential,
+        &[
+            0xf7fcfd, 0xe0ecf4, 0xbfd3e6, 0x9ebcda, 0x8c96c6, 0x8c6bb1, 0x88419d, 0x810f7c,
+            0x4d004b,
+        ],
+    ),
+    colormap(
+        "GnBu",
+        Sequential,
+        &[
+            0xf7fcf0, 0xe0f3db, 0xccebc5, 0xa8ddb5, 0x7bccc4, 0x4eb3d3, 0x2b8cbe, 0x0868ac,
+            0x084081,
+        ],
+    ),
+    colormap(
+        "Greens",
+        Sequential,
+        &[
+            0xf7fcf5, 0xe5f5e0, 0xc7e9c0, 0xa1d99b, 0x74c476, 0x41ab5d, 0x238b45, 0x006d2c,
+            0x00441b,
+        ],
+    ),
+    colormap(
+        "Greys",
+        Sequential,
+        &[
+            0xffffff, 0xf0f0f0, 0xd9d9d9, 0xbdbdbd, 0x969696, 0x737373, 0x525252, 0x252525,
+            0x000000,
+        ],
+    ),
+    colormap(
+        "Oranges",
+        Sequential,
+        &[
+            0xfff5eb, 0xfee6ce, 0xfdd0a2, 0xfdae6b, 0xfd8d3c, 0xf16913, 0xd94801, 0xa63603,
+            0x7f2704,
+        ],
+    ),
+    colormap(
+        "OrRd",
+        Sequential,
+        &[
+            0xfff7ec, 0xfee8c8, 0xfdd49e, 0xfdbb84, 0xfc8d59, 0xef6548, 0xd7301f, 0xb30000,
+            0x7f0000,
+        ],
+    ),
+    colormap(
+        "PuBu",
+        Sequential,
+        &[
+            0xfff7fb, 0xece7f2, 0xd0d1e6, 0xa6bddb, 0x74a9cf, 0x3690c0, 0x0570b0, 0x045a8d,
+            0x023858,
+        ],
+    ),
+    colormap(
+        "PuBuGn",
+        Sequential,
+        &[
+            0xfff7fb, 0xece2f0, 0xd0d1e6, 0xa6bddb, 0x67a9cf, 0x3690c0, 0x02818a, 0x016c59,
+            0x014636,
+        ],
+    ),
+    colormap(
+        "PuRd",
+        Sequential,
+        &[
+            0xf7f4f9, 0xe7e1ef, 0xd4b9da, 0xc994c7, 0xdf65b0, 0xe7298a, 0xce1256, 0x980043,
+            0x67001f,
+        ],
+    ),
+    colormap(
+        "Purples",
+        Sequential,
+        &[
+            0xfcfbfd, 0xefedf5, 0xdadaeb, 0xbcbddc, 0x9e9ac8, 0x807dba, 0x6a51a3, 0x54278f,
+            0x3f007d,
+        ],
+    ),
+    colormap(
+        "RdPu",
+        Sequential,
+        &[
+            0xfff7f3, 0xfde0dd, 0xfcc5c0, 0xfa9fb5, 0xf768a1, 0xdd3497, 0xae017e, 0x7a0177,
+            0x49006a,
+        ],
+    ),
+    colormap(
+        "Reds",
+        Sequential,
+        &[
+            0xfff5f0, 0xfee0d2, 0xfcbba1, 0xfc9272, 0xfb6a4a, 0xef3b2c, 0xcb181d, 0xa50f15,
+            0x67000d,
+        ],
+    ),
+    colormap(
+        "YlGn",
+        Sequential,
+        &[
+            0xffffe5, 0xf7fcb9, 0xd9f0a3, 0xaddd8e, 0x78c679, 0x41ab5d, 0x238443, 0x006837,
+            0x004529,
+        ],
+    ),
+    colormap(
+        "YlGnBu",
+        Sequential,
+        &[
+            0xffffd9, 0xedf8b1, 0xc7e9b4, 0x7fcdbb, 0x41b6c4, 0x1d91c0, 0x225ea8, 0x253494,
+            0x081d58,
+        ],
+    ),
+    colormap(
+        "YlOrBr",
+        Sequential,
+        &[
+            0xffffe5, 0xfff7bc, 0xfee391, 0xfec44f, 0xfe9929, 0xec7014, 0xcc4c02, 0x993404,
+            0x662506,
+        ],
+    ),
+    colormap(
+        "YlOrRd",
+        Sequential,
+        &[
+            0xffffcc, 0xffeda0, 0xfed976, 0xfeb24c, 0xfd8d3c, 0xfc4e2a, 0xe31a1c, 0xbd0026,
+            0x800026,
+        ],
+    ),
+    // ColorBrewer, diverging
+    colormap(
+        "BrBG",
+        Diverging,
+        &[
+            0x543005, 0x8c510a, 0xbf812d, 0xdfc27d, 0xf6e8c3, 0xf5f5f5, 0xc7eae5, 0x80cdc1,
+            0x35978f, 0x01665e, 0x003c30,
+        ],
+    ),
+    colormap(
+        "PiYG",
+        Diverging,
+        &[
+            0x8e0152, 0xc51b7d, 0xde77ae, 0xf1b6da, 0xfde0ef, 0xf7f7f7, 0xe6f5d0, 0xb8e186,
+            0x7fbc41, 0x4d9221, 0x276419,
+        ],
+    ),
+    colormap(
+        "PRGn",
+        Diverging,
+        &[
+            0x40004b, 0x762a83, 0x9970ab, 0xc2a5cf, 0xe7d4e8, 0xf7f7f7, 0xd9f0d3, 0xa6dba0,
+            0x5aae61, 0x1b7837, 0x00441b,
+        ],
+    ),
+    colormap(
+        "PuOr",
+        Diverging,
+        &[
+            0x7f3b08, 0xb35806, 0xe08214, 0xfdb863, 0xfee0b6, 0xf7f7f7, 0xd8daeb, 0xb2abd2,
+            0x8073ac, 0x542788, 0x2d004b,
+        ],
+    ),
+    colormap(
+        "RdBu",
+        Diverging,
+        &[
+            0x67001f, 0xb2182b, 0xd6604d, 0xf4a582, 0xfddbc7, 0xf7f7f7, 0xd1e5f0, 0x92c5de,
+            0x4393c3, 0x2166ac, 0x053061,
+        ],
+    ),
+    colormap(
+        "RdGy",
+        Diverging,
+        &[
+            0x67001f, 0xb2182b, 0xd6604d, 0xf4a582, 0xfddbc7, 0xffffff, 0xe0e0e0, 0xbababa,
+            0x878787, 0x4d4d4d, 0x1a1a1a,
+        ],
+    ),
+    colormap(
+        "RdYlBu",
+        Diverging,
+        &[
+            0xa50026, 0xd73027, 0xf46d43, 0xfdae61, 0xfee090, 0xffffbf, 0xe0f3f8, 0xabd9e9,
+            0x74add1, 0x4575b4, 0x313695,
+        ],
+    ),
+    colormap(
+        "RdYlGn",
+        Diverging,
+        &[
+            0xa50026, 0xd73027, 0xf46d43, 0xfdae61, 0xfee08b, 0xffffbf, 0xd9ef8b, 0xa6d96a,
+            0x66bd63, 0x1a9850, 0x006837,
+        ],
+    ),
+    colormap(
+        "Spectral",
+        Diverging,
+        &[
+            0x9e0142, 0xd53e4f, 0xf46d43, 0xfdae61, 0xfee08b, 0xffffbf, 0xe6f598, 0xabdda4,
+            0x66c2a5, 0x3288bd, 0x5e4fa2,
+        ],
+    ),
+    // ColorBrewer, qualitative
+    colormap(
+        "Accent",
+        Qualitative,
+        &[
+            0x7fc97f, 0xbeaed4, 0xfdc086, 0xffff99, 0x386cb0, 0xf0027f, 0xbf5b17, 0x666666,
+        ],
+    ),
+    colormap(
+        "Dark2",
+        Qualitative,
+        &[
+            0x1b9e77, 0xd95f02, 0x7570b3, 0xe7298a, 0x66a61e, 0xe6ab02, 0xa6761d, 0x666666,
+        ],
+    ),
+    colormap(
+        "Paired",
+        Qualitative,
+        &[
+            0xa6cee3, 0x1f78b4, 0xb2df8a, 0x33a02c, 0xfb9a99, 0xe31a1c, 0xfdbf6f, 0xff7f00,
+            0xcab2d6, 0x6a3d9a, 0xffff99, 0xb15928,
+        ],
+    ),
+    colormap(
+        "Pastel1",
+        Qualitative,
+        &[
+            0xfbb4ae, 0xb3cde3, 0xccebc5, 0xdecbe4, 0xfed9a6, 0xffffcc, 0xe5d8bd, 0xfddaec,
+            0xf2f2f2,
+        ],
+    ),
+    colormap(
+        "Pastel2",
+        Qualitative,
+        &[
+            0xb3e2cd, 0xfdcdac, 0xcbd5e8, 0xf4cae4, 0xe6f5c9, 0xfff2ae, 0xf1e2cc, 0xcccccc,
+        ],
+    ),
+    colormap(
+        "Set1",
+        Qualitative,
+        &[
+            0xe41a1c, 0x377eb8, 0x4daf4a, 0x984ea3, 0xff7f00, 0xffff33, 0xa65628, 0xf781bf,
+            0x999999,
+        ],
+    ),
+    colormap(
+        "Set2",
+        Qualitative,
+        &[
+            0x66c2a5, 0xfc8d62, 0x8da0cb, 0xe78ac3, 0xa6d854, 0xffd92f, 0xe5c494, 0xb3b3b3,
+        ],
+    ),
+    colormap(
+        "Set3",
+        Qualitative,
+        &[
+            0x8dd3c7, 0xffffb3, 0xbebada, 0xfb8072, 0x80b1d3, 0xfdb462, 0xb3de69, 0xfccde5,
+            0xd9d9d9, 0xbc80bd, 0xccebc5, 0xffed6f,
+        ],
+    ),
+];
+
+/// Look up a colormap by its name (case-insensitive).
+pub fn colormap_by_name(name: &str) -> Option<&'static Colormap> {
+    COLORMAPS
+        .iter()
+        .find(|colormap| colormap.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RGBA;
+
+    #[test]
+    fn lookup_by_name() {
+        assert_eq!(colormap_by_name("viridis").unwrap().name, "viridis");
+        assert_eq!(colormap_by_name("ylgnbu").unwrap().name, "YlGnBu");
+        assert_eq!(colormap_by_name("Set1").unwrap().kind, Qualitative);
+        assert!(colormap_by_name("unknown").is_none());
+    }
+
+    #[test]
+    fn colormap_colors() {
+        let viridis = colormap_by_name("viridis").unwrap();
+        let colors = viridis.colors();
+
+        assert_eq!(colors.len(), 11);
+        assert_eq!(colors[0], Color::from_rgb(0x44, 0x01, 0x54));
+        assert_eq!(colors[10], Color::from_rgb(0xfd, 0xe7, 0x25));
+    }
+
+    #[test]
+    fn colormap_color_scale() {
+        let mix = Color::mix::<RGBA<f64>>;
+
+        for colormap in COLORMAPS {
+            let colors = colormap.colors();
+            let color_scale = colormap.color_scale();
+
+            let first = color_scale.sample(Fraction::from(0.0), &mix);
+            let last = color_scale.sample(Fraction::from(1.0), &mix);
+
+            assert_eq!(first.as_ref(), colors.first());
+            assert_eq!(last.as_ref(), colors.last());
+        }
+    }
+}
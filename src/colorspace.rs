@@ -1,4 +1,4 @@
-use crate::helper::Fraction;
+use crate::helper::{Fraction, HueInterpolation};
 use crate::Color;
 
 pub trait ColorSpace {
@@ -6,4 +6,28 @@ pub trait ColorSpace {
     fn into_color(self) -> Color;
 
     fn mix(&self, other: &Self, fraction: Fraction) -> Self;
+
+    /// Like `mix`, but use the given method to interpolate the hue. Color spaces without a hue
+    /// component ignore the hue interpolation method.
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, _hue: HueInterpolation) -> Self
+    where
+        Self: Sized,
+    {
+        self.mix(other, fraction)
+    }
+
+    /// Like `mix_with_hue`, but interpolate with premultiplied alpha. The color components are
+    /// weighted by their alpha value, such that (semi-)transparent colors contribute less to the
+    /// result. For example, mixing a transparent color with red only changes the alpha value.
+    /// The hue is not premultiplied.
+    ///
+    /// See: <https://www.w3.org/TR/css-color-4/#interpolation-alpha>
+    ///
+    /// The default implementation does not premultiply and falls back to `mix_with_hue`.
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self
+    where
+        Self: Sized,
+    {
+        self.mix_with_hue(other, fraction, hue)
+    }
 }
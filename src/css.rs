@@ -0,0 +1,507 @@
+//! Parsers for the color functions of CSS Color Module Level 5: relative colors like
+//! `rgb(from #ff0000 r g calc(b + 20))` and color mixing like `color-mix(in oklch, red 40%, blue)`.
+//!
+//! See: <https://www.w3.org/TR/css-color-5/>
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{alpha1, char, one_of, space0, space1},
+    combinator::{all_consuming, opt, verify},
+    error::{context, ErrorKind, ParseError},
+    number::complete::double,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
+
+use crate::{
+    oklab::{OkLCh, OkLab},
+    parser::{closing_parenthesis, color, component, percentage, IResult, SyntaxError},
+    types::Scalar,
+    Color, Fraction, HueInterpolation, LCh, Lab, LinearRGBA, HSLA, HWBA, RGBA,
+};
+
+fn fail<T>(input: &str, kind: ErrorKind) -> IResult<&str, T> {
+    Err(nom::Err::Error(ParseError::from_error_kind(input, kind)))
+}
+
+/// A color function that supports the relative color syntax (`hsl(from red h s calc(l - 20))`).
+struct RelativeSyntax {
+    names: &'static [&'static str],
+    channels: [&'static str; 3],
+    /// The value that corresponds to 100% (`None` for hue channels)
+    percent_reference: [Option<Scalar>; 3],
+    to_channels: fn(&Color) -> [Scalar; 4],
+    from_channels: fn([Scalar; 4]) -> Color,
+}
+
+static RELATIVE_SYNTAX: &[RelativeSyntax] = &[
+    RelativeSyntax {
+        names: &["rgb", "rgba"],
+        channels: ["r", "g", "b"],
+        percent_reference: [Some(255.0), Some(255.0), Some(255.0)],
+        to_channels: |c| {
+            let rgba = c.to_rgba_float();
+            [255.0 * rgba.r, 255.0 * rgba.g, 255.0 * rgba.b, rgba.alpha]
+        },
+        from_channels: |[r, g, b, alpha]| {
+            Color::from_rgba_float(r / 255.0, g / 255.0, b / 255.0, alpha)
+        },
+    },
+    RelativeSyntax {
+        names: &["hsl", "hsla"],
+        channels: ["h", "s", "l"],
+        percent_reference: [None, Some(100.0), Some(100.0)],
+        to_channels: |c| {
+            let hsla = c.to_hsla();
+            [hsla.h, 100.0 * hsla.s, 100.0 * hsla.l, hsla.alpha]
+        },
+        from_channels: |[h, s, l, alpha]| Color::from_hsla(h, s / 100.0, l / 100.0, alpha),
+    },
+    RelativeSyntax {
+        names: &["hwb"],
+        channels: ["h", "w", "b"],
+        percent_reference: [None, Some(100.0), Some(100.0)],
+        to_channels: |c| {
+            let hwba = c.to_hwba();
+            [hwba.h, 100.0 * hwba.w, 100.0 * hwba.b, hwba.alpha]
+        },
+        from_channels: |[h, w, b, alpha]| Color::from_hwba(h, w / 100.0, b / 100.0, alpha),
+    },
+    RelativeSyntax {
+        names: &["lab"],
+        channels: ["l", "a", "b"],
+        percent_reference: [Some(100.0), Some(125.0), Some(125.0)],
+        to_channels: |c| {
+            let lab = c.to_lab();
+            [lab.l, lab.a, lab.b, lab.alpha]
+        },
+        from_channels: |[l, a, b, alpha]| Color::from_lab(l, a, b, alpha),
+    },
+    RelativeSyntax {
+        names: &["lch"],
+        channels: ["l", "c", "h"],
+        percent_reference: [Some(100.0), Some(150.0), None],
+        to_channels: |c| {
+            let lch = c.to_lch();
+            [lch.l, lch.c, lch.h, lch.alpha]
+        },
+        from_channels: |[l, c, h, alpha]| Color::from_lch(l, c, h, alpha),
+    },
+    RelativeSyntax {
+        names: &["oklab"],
+        channels: ["l", "a", "b"],
+        percent_reference: [Some(1.0), Some(0.4), Some(0.4)],
+        to_channels: |c| {
+            let lab = OkLab::from(c);
+            [lab.l, lab.a, lab.b, lab.alpha]
+        },
+        from_channels: |[l, a, b, alpha]| Color::from(&OkLab::with_alpha(l, a, b, alpha)),
+    },
+    RelativeSyntax {
+        names: &["oklch"],
+        channels: ["l", "c", "h"],
+        percent_reference: [Some(1.0), Some(0.4), None],
+        to_channels: |c| {
+            let lch = OkLCh::from(c);
+            [lch.l, lch.c, lch.h, lch.alpha]
+        },
+        from_channels: |[l, c, h, alpha]| Color::from(&OkLCh::with_alpha(l, c, h, alpha)),
+    },
+];
+
+/// The channel keywords and their values for evaluating a channel of a relative color.
+struct Context<'a> {
+    syntax: &'a RelativeSyntax,
+    values: [Scalar; 4],
+    percent_reference: Option<Scalar>,
+}
+
+impl Context<'_> {
+    fn channel(&self, keyword: &str) -> Option<Scalar> {
+        let keyword = keyword.to_lowercase();
+        if keyword == "alpha" {
+            return Some(self.values[3]);
+        }
+        self.syntax
+            .channels
+            .iter()
+            .position(|&channel| channel == keyword)
+            .map(|i| self.values[i])
+    }
+}
+
+/// A number with an optional unit: a percentage of the channel or an angle (converted to
+/// degrees).
+fn dimension<'a>(input: &'a str, ctx: &Context) -> IResult<&'a str, Scalar> {
+    let (rest, value) = double(input)?;
+    let (rest, unit) = opt(alt((
+        tag("%"),
+        tag("deg"),
+        tag("grad"),
+        tag("rad"),
+        tag("turn"),
+    )))(rest)?;
+
+    let value = match unit {
+        None | Some("deg") => value,
+        Some("%") => match ctx.percent_reference {
+            Some(reference) => value * reference / 100.0,
+            None => return fail(input, ErrorKind::Tag),
+        },
+        Some("grad") => value * 360.0 / 400.0,
+        Some("rad") => value.to_degrees(),
+        Some("turn") => value * 360.0,
+        Some(_) => unreachable!(),
+    };
+
+    Ok((rest, value))
+}
+
+fn keyword<'a>(input: &'a str, ctx: &Context) -> IResult<&'a str, Scalar> {
+    let (rest, word) = alpha1(input)?;
+    match ctx.channel(word) {
+        Some(value) => Ok((rest, value)),
+        None => fail(input, ErrorKind::Tag),
+    }
+}
+
+/// A single value: a number, a channel keyword, `calc(…)` or a parenthesized expression.
+fn value<'a>(input: &'a str, ctx: &Context) -> IResult<&'a str, Scalar> {
+    let expression = |input| delimited(space0, |i| sum(i, ctx), space0)(input);
+
+    alt((
+        delimited(tag_no_case("calc("), expression, char(')')),
+        delimited(char('('), expression, char(')')),
+        |i| dimension(i, ctx),
+        |i| keyword(i, ctx),
+        |i| preceded(char('-'), |i| value(i, ctx))(i).map(|(i, v)| (i, -v)),
+    ))(input)
+}
+
+fn operation<'a>(
+    input: &'a str,
+    ctx: &Context,
+    operators: &'static str,
+    operand: fn(&'a str, &Context) -> IResult<&'a str, Scalar>,
+) -> IResult<&'a str, Scalar> {
+    let (mut input, mut result) = operand(input, ctx)?;
+
+    while let Ok((rest, operator)) =
+        delimited(space0, one_of::<_, _, SyntaxError<_>>(operators), space0)(input)
+    {
+        let (rest, rhs) = operand(rest, ctx)?;
+        result = match operator {
+            '+' => result + rhs,
+            '-' => result - rhs,
+            '*' => result * rhs,
+            '/' => result / rhs,
+            _ => unreachable!(),
+        };
+        input = rest;
+    }
+
+    Ok((input, result))
+}
+
+fn product<'a>(input: &'a str, ctx: &Context) -> IResult<&'a str, Scalar> {
+    operation(input, ctx, "*/", value)
+}
+
+fn sum<'a>(input: &'a str, ctx: &Context) -> IResult<&'a str, Scalar> {
+    operation(input, ctx, "+-", product)
+}
+
+/// A color argument of a function: everything up to the next whitespace, comma or closing
+/// parenthesis (at the same nesting level).
+fn color_argument(input: &str) -> IResult<&str, Color> {
+    let mut depth = 0;
+    let end = input
+        .char_indices()
+        .find(|&(_, c)| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' if depth == 0 => true,
+            ')' => {
+                depth -= 1;
+                false
+            }
+            c => depth == 0 && (c.is_whitespace() || c == ','),
+        })
+        .map_or(input.len(), |(i, _)| i);
+
+    if end == 0 {
+        return fail(input, ErrorKind::Verify);
+    }
+    let (_, c) = color(&input[..end])?;
+    Ok((&input[end..], c))
+}
+
+fn relative_color(input: &str) -> IResult<&str, Color> {
+    let (rest, name) = terminated(alpha1, char('('))(input)?;
+    let name = name.to_lowercase();
+    let syntax = match RELATIVE_SYNTAX
+        .iter()
+        .find(|syntax| syntax.names.contains(&name.as_str()))
+    {
+        Some(syntax) => syntax,
+        None => return fail(input, ErrorKind::Tag),
+    };
+
+    let (rest, _) = tuple((space0, tag_no_case("from"), space1))(rest)?;
+    let (rest, origin) = color_argument(rest)?;
+
+    let values = (syntax.to_channels)(&origin);
+    let channel_context = |i: usize| Context {
+        syntax,
+        values,
+        percent_reference: syntax.percent_reference[i],
+    };
+
+    let (rest, c1) = component(CHANNEL, space1, |i| value(i, &channel_context(0)))(rest)?;
+    let (rest, c2) = component(CHANNEL, space1, |i| value(i, &channel_context(1)))(rest)?;
+    let (rest, c3) = component(CHANNEL, space1, |i| value(i, &channel_context(2)))(rest)?;
+
+    let alpha_context = Context {
+        syntax,
+        values,
+        percent_reference: Some(1.0),
+    };
+    let (rest, alpha) = match opt(delimited(space0, char('/'), space0))(rest)? {
+        (after_slash, Some(_)) => context(CHANNEL, |i| value(i, &alpha_context))(after_slash)?,
+        (rest, None) => (rest, values[3]),
+    };
+    let (rest, _) = closing_parenthesis(rest)?;
+
+    Ok((rest, (syntax.from_channels)([c1, c2, c3, alpha])))
+}
+
+const CHANNEL: &str = "expected a channel value (a number, a channel keyword or calc(…))";
+
+/// Parse a color in relative color syntax, like `lch(from red l c calc(h + 180))`. The channel
+/// keywords refer to the channels of the origin color in the color space of the function.
+pub(crate) fn parse_relative_color(input: &str) -> IResult<&str, Color> {
+    all_consuming(relative_color)(input.trim())
+}
+
+fn hue_interpolation(input: &str) -> IResult<&str, HueInterpolation> {
+    let (rest, method) = terminated(
+        alt((
+            tag_no_case("shorter"),
+            tag_no_case("longer"),
+            tag_no_case("increasing"),
+            tag_no_case("decreasing"),
+        )),
+        pair(space1, tag_no_case("hue")),
+    )(input)?;
+
+    let method = match method.to_lowercase().as_ref() {
+        "shorter" => HueInterpolation::Shorter,
+        "longer" => HueInterpolation::Longer,
+        "increasing" => HueInterpolation::Increasing,
+        _ => HueInterpolation::Decreasing,
+    };
+    Ok((rest, method))
+}
+
+/// A color with an optional percentage (`red 40%` or `40% red`).
+fn mix_argument(input: &str) -> IResult<&str, (Color, Option<Scalar>)> {
+    alt((
+        |i| {
+            let (i, p) = terminated(percentage, space1)(i)?;
+            let (i, c) = color_argument(i)?;
+            Ok((i, (c, Some(p))))
+        },
+        |i| {
+            let (i, c) = color_argument(i)?;
+            let (i, p) = opt(preceded(space1, percentage))(i)?;
+            Ok((i, (c, p)))
+        },
+    ))(input)
+}
+
+/// The color spaces that are supported by [`mix_in`].
+const MIX_SPACES: &[&str] = &[
+    "srgb",
+    "srgb-linear",
+    "hsl",
+    "hwb",
+    "lab",
+    "lch",
+    "oklab",
+    "oklch",
+];
+
+fn mix_in(
+    space: &str,
+    c1: &Color,
+    c2: &Color,
+    fraction: Fraction,
+    hue: HueInterpolation,
+) -> Option<Color> {
+    let color = match space.to_lowercase().as_ref() {
+        "srgb" => c1.mix_premultiplied::<RGBA<f64>>(c2, fraction, hue),
+        "srgb-linear" => c1.mix_premultiplied::<LinearRGBA>(c2, fraction, hue),
+        "hsl" => c1.mix_premultiplied::<HSLA>(c2, fraction, hue),
+        "hwb" => c1.mix_premultiplied::<HWBA>(c2, fraction, hue),
+        "lab" => c1.mix_premultiplied::<Lab>(c2, fraction, hue),
+        "lch" => c1.mix_premultiplied::<LCh>(c2, fraction, hue),
+        "oklab" => c1.mix_premultiplied::<OkLab>(c2, fraction, hue),
+        "oklch" => c1.mix_premultiplied::<OkLCh>(c2, fraction, hue),
+        _ => return None,
+    };
+    Some(color)
+}
+
+const MIX_COLOR: &str = "expected a color, optionally with a percentage";
+
+fn color_mix(input: &str) -> IResult<&str, Color> {
+    let (rest, _) = tag_no_case("color-mix(")(input)?;
+    let (rest, _) = preceded(space0, context("expected 'in'", tag_no_case("in")))(rest)?;
+    let (rest, space) = component(
+        "expected a color space (srgb, srgb-linear, hsl, hwb, lab, lch, oklab or oklch)",
+        space1,
+        verify(
+            take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-'),
+            |space: &str| MIX_SPACES.contains(&space.to_lowercase().as_str()),
+        ),
+    )(rest)?;
+    let (rest, hue) = opt(preceded(space1, hue_interpolation))(rest)?;
+    let separator = |i| tuple((space0, char(','), space0))(i);
+    let arguments = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    let (rest, (c1, p1)) = component(MIX_COLOR, separator, mix_argument)(rest)?;
+    let (rest, (c2, p2)) = component(MIX_COLOR, separator, mix_argument)(rest)?;
+    let (rest, _) = closing_parenthesis(rest)?;
+
+    // see https://www.w3.org/TR/css-color-5/#color-mix-percent-norm
+    let (p1, p2) = match (p1, p2) {
+        (None, None) => (0.5, 0.5),
+        (Some(p1), None) => (p1, 1.0 - p1),
+        (None, Some(p2)) => (1.0 - p2, p2),
+        (Some(p1), Some(p2)) => (p1, p2),
+    };
+    let total = p1 + p2;
+    if p1 < 0.0 || p2 < 0.0 || total <= 0.0 {
+        // the input is a color-mix() expression, so there is no need to try the other parsers
+        return Err(nom::Err::Failure(SyntaxError::expected(
+            arguments,
+            "percentages must not be negative and must not add up to zero",
+        )));
+    }
+
+    let hue = hue.unwrap_or(HueInterpolation::Shorter);
+    let mixed = match mix_in(space, &c1, &c2, Fraction::from(p2 / total), hue) {
+        Some(color) => color,
+        None => return fail(input, ErrorKind::Tag),
+    };
+
+    // if the percentages sum up to less than 100%, the result is partially transparent
+    let color = if total < 1.0 {
+        mixed.with_alpha(mixed.to_rgba_float().alpha * total)
+    } else {
+        mixed
+    };
+
+    Ok((rest, color))
+}
+
+/// Parse a `color-mix(in <color space> [<method> hue], <color> [<p>], <color> [<p>])`
+/// expression. The colors are mixed with premultiplied alpha, like in CSS.
+pub(crate) fn parse_color_mix(input: &str) -> IResult<&str, Color> {
+    all_consuming(color_mix)(input.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_color;
+
+    fn parse(input: &str) -> Option<Color> {
+        parse_color(input)
+    }
+
+    #[test]
+    fn relative_color_syntax() {
+        assert_eq!(Some(Color::red()), parse("rgb(from red r g b)"));
+        assert_eq!(
+            Some(Color::from_rgb(255, 0, 20)),
+            parse("rgb(from #ff0000 r g calc(b + 20))")
+        );
+        assert_eq!(
+            Some(Color::from_rgb(128, 0, 0)),
+            parse("rgb(from red calc(r / 2) 0 0)")
+        );
+        assert_eq!(
+            Some(Color::from_rgba(255, 0, 0, 0.5)),
+            parse("rgb(from red r g b / calc(alpha * 0.5))")
+        );
+        assert_eq!(
+            Some(Color::from_rgb(0, 255, 0)),
+            parse("rgb(from red 0% 100% b)")
+        );
+        assert_eq!(
+            Some(Color::from_hsl(180.0, 1.0, 0.5)),
+            parse("hsl(from red calc(h + 180) s l)")
+        );
+        assert_eq!(
+            Some(Color::from_hsl(120.0, 1.0, 0.25)),
+            parse("hsl(from rgb(0 255 0) h s calc(l - 25))")
+        );
+        assert_eq!(
+            Some(Color::from_hsl(0.0, 1.0, 0.5)),
+            parse("hsl(from red calc((h + 0.5turn) * 2) s l)")
+        );
+
+        let lch = Color::from_rgb(0, 0, 255).to_lch();
+        assert_eq!(
+            Some(Color::from_lch(lch.l, lch.c, lch.h + 180.0, 1.0)),
+            parse("lch(from blue l c calc(h + 180))")
+        );
+
+        assert_eq!(None, parse("rgb(from red r g)"));
+        assert_eq!(None, parse("rgb(from red r g x)"));
+        assert_eq!(None, parse("rgb(from nocolor r g b)"));
+        assert_eq!(None, parse("hsl(from red 50% s l)"));
+    }
+
+    #[test]
+    fn color_mix() {
+        assert_eq!(
+            Some(Color::from_rgb(128, 0, 128)),
+            parse("color-mix(in srgb, red, blue)")
+        );
+        assert_eq!(
+            Some(Color::from_rgb(102, 0, 153)),
+            parse("color-mix(in srgb, red 40%, blue)")
+        );
+        assert_eq!(
+            Some(Color::from_rgb(102, 0, 153)),
+            parse("color-mix(in srgb, 40% red, blue 60%)")
+        );
+        assert_eq!(
+            Some(Color::from_rgba(128, 0, 128, 0.5)),
+            parse("color-mix(in srgb, red 25%, blue 25%)")
+        );
+        assert_eq!(
+            Some(Color::red().mix_premultiplied::<OkLCh>(
+                &Color::from_rgb(0, 0, 255),
+                Fraction::from(0.6),
+                HueInterpolation::Shorter
+            )),
+            parse("color-mix(in oklch, red 40%, blue)")
+        );
+        assert_eq!(
+            Some(Color::red().mix_premultiplied::<HSLA>(
+                &Color::from_rgb(0, 0, 255),
+                Fraction::from(0.5),
+                HueInterpolation::Longer
+            )),
+            parse("color-mix(in hsl longer hue, red, rgb(0 0 255))")
+        );
+
+        assert_eq!(None, parse("color-mix(in foo, red, blue)"));
+        assert_eq!(None, parse("color-mix(in srgb, red 0%, blue 0%)"));
+        assert_eq!(None, parse("color-mix(in srgb, red)"));
+    }
+}
@@ -1,13 +1,20 @@
-use core::f64 as scalar;
+use std::cmp::Ordering;
+use std::fmt;
 
 use rand::prelude::*;
+use rayon::prelude::*;
 
 use crate::delta_e;
+use crate::helper::mod_positive;
 use crate::random::{self, RandomizationStrategy};
-use crate::{Color, Lab};
+use crate::{Color, LCh, Lab};
 
 type Scalar = f64;
 
+/// The number of random candidates that are tried when looking for a color that satisfies the
+/// given `ColorConstraints`.
+const MAX_CONSTRAINT_ATTEMPTS: usize = 1_000;
+
 #[derive(Clone)]
 pub struct DistanceResult {
     /// The closest distance between any two colors
@@ -54,6 +61,92 @@ pub enum DistanceMetric {
     CIEDE2000,
 }
 
+/// Restrictions on the region of admissible colors. The colors that are modified during the
+/// optimization will always satisfy these constraints. Fixed colors are exempt.
+#[derive(Debug, Clone)]
+pub struct ColorConstraints {
+    /// Minimum LCh lightness (between 0.0 and 100.0)
+    pub min_lightness: Scalar,
+
+    /// Maximum LCh lightness (between 0.0 and 100.0)
+    pub max_lightness: Scalar,
+
+    /// Minimum LCh chroma
+    pub min_chroma: Scalar,
+
+    /// Maximum LCh chroma
+    pub max_chroma: Scalar,
+
+    /// LCh hue ranges `(start, end)` in degrees that must be avoided. If `start` is larger than
+    /// `end`, the range wraps around 360°, i.e. `(330.0, 60.0)` excludes reddish hues.
+    pub excluded_hues: Vec<(Scalar, Scalar)>,
+
+    /// An optional background color along with the minimum WCAG contrast ratio that each color
+    /// must have with respect to the background.
+    pub background: Option<(Color, Scalar)>,
+}
+
+impl Default for ColorConstraints {
+    fn default() -> Self {
+        ColorConstraints {
+            min_lightness: 0.0,
+            max_lightness: 100.0,
+            min_chroma: 0.0,
+            max_chroma: Scalar::INFINITY,
+            excluded_hues: vec![],
+            background: None,
+        }
+    }
+}
+
+impl ColorConstraints {
+    /// Check whether the given color lies within the admissible region.
+    pub fn is_satisfied(&self, color: &Color) -> bool {
+        self.is_satisfied_lab(color, &color.to_lab())
+    }
+
+    /// Like `is_satisfied`, but avoids recomputing the Lab coordinates of the color.
+    fn is_satisfied_lab(&self, color: &Color, lab: &Lab) -> bool {
+        let lch = LCh::from(lab);
+
+        if lch.l < self.min_lightness || lch.l > self.max_lightness {
+            return false;
+        }
+
+        if lch.c < self.min_chroma || lch.c > self.max_chroma {
+            return false;
+        }
+
+        let hue_excluded = |&(start, end): &(Scalar, Scalar)| {
+            let start = mod_positive(start, 360.0);
+            let end = mod_positive(end, 360.0);
+            if start <= end {
+                lch.h >= start && lch.h <= end
+            } else {
+                lch.h >= start || lch.h <= end
+            }
+        };
+        if self.excluded_hues.iter().any(hue_excluded) {
+            return false;
+        }
+
+        match &self.background {
+            Some((background, min_contrast)) => color.contrast_ratio(background) >= *min_contrast,
+            None => true,
+        }
+    }
+
+    /// Generate a random color within the admissible region. Returns `None` if no such color
+    /// could be found after a fixed number of attempts.
+    fn generate_with(&self, rng: &mut dyn RngCore) -> Option<Color> {
+        let mut strategy = random::strategies::UniformRGB;
+
+        (0..MAX_CONSTRAINT_ATTEMPTS)
+            .map(|_| strategy.generate_with(rng))
+            .find(|color| self.is_satisfied(color))
+    }
+}
+
 pub struct SimulationParameters {
     pub initial_temperature: Scalar,
     pub cooling_rate: Scalar,
@@ -62,6 +155,7 @@ pub struct SimulationParameters {
     pub opt_mode: OptimizationMode,
     pub distance_metric: DistanceMetric,
     pub num_fixed_colors: usize,
+    pub constraints: ColorConstraints,
 }
 
 pub struct SimulatedAnnealing<R: Rng> {
@@ -105,37 +199,58 @@ impl<R: Rng> SimulatedAnnealing<R> {
         }
     }
 
-    fn modify_color_and_lab(&mut self, color: &mut Color, lab: &mut Lab) {
+    /// Randomly modify the given color. Candidates that violate the color constraints are
+    /// rejected. If no admissible candidate is found, the color is left unchanged and `false` is
+    /// returned.
+    fn modify_color_and_lab(&mut self, color: &mut Color, lab: &mut Lab) -> bool {
         let mut strategy = random::strategies::UniformRGB {};
 
-        match self.parameters.opt_mode {
-            OptimizationMode::Local => {
-                let mut rgb = color.to_rgba();
-                self.modify_channel(&mut rgb.r);
-                self.modify_channel(&mut rgb.g);
-                self.modify_channel(&mut rgb.b);
-                *color = Color::from_rgb(rgb.r, rgb.g, rgb.b);
-            }
-            OptimizationMode::Global => {
-                *color = strategy.generate_with(&mut self.rng);
+        for _ in 0..MAX_CONSTRAINT_ATTEMPTS {
+            let candidate = match self.parameters.opt_mode {
+                OptimizationMode::Local => {
+                    let mut rgb = color.to_rgba();
+                    self.modify_channel(&mut rgb.r);
+                    self.modify_channel(&mut rgb.g);
+                    self.modify_channel(&mut rgb.b);
+                    Color::from_rgb(rgb.r, rgb.g, rgb.b)
+                }
+                OptimizationMode::Global => strategy.generate_with(&mut self.rng),
+            };
+            let candidate_lab = candidate.to_lab();
+
+            if self
+                .parameters
+                .constraints
+                .is_satisfied_lab(&candidate, &candidate_lab)
+            {
+                *color = candidate;
+                *lab = candidate_lab;
+                return true;
             }
         }
-        *lab = color.to_lab();
+
+        false
     }
 
     pub fn run(&mut self, callback: &mut dyn FnMut(&IterationStatistics)) -> DistanceResult {
         self.temperature = self.parameters.initial_temperature;
 
-        let mut result = DistanceResult::new(
-            &self.lab_values,
-            self.parameters.distance_metric,
-            self.parameters.num_fixed_colors,
-        );
+        let distance_metric = self.parameters.distance_metric;
+
+        let mut matrix = DistanceMatrix::new(&self.lab_values, distance_metric);
+        let mut result =
+            DistanceResult::new(&matrix, distance_metric, self.parameters.num_fixed_colors);
 
         if self.parameters.num_fixed_colors == self.colors.len() {
             return result;
         }
 
+        let mut admissible: Vec<bool> = self
+            .colors
+            .iter()
+            .map(|c| self.parameters.constraints.is_satisfied(c))
+            .collect();
+
         for iter in 0..self.parameters.num_iterations {
             let random_index = if self.parameters.opt_target == OptimizationTarget::Mean {
                 self.rng
@@ -160,13 +275,26 @@ impl<R: Rng> SimulatedAnnealing<R> {
                 "cannot change fixed color"
             );
 
-            let mut new_colors = self.colors[random_index].clone();
+            let mut new_color = self.colors[random_index].clone();
+            let mut new_lab = self.lab_values[random_index].clone();
 
-            let mut new_lab_values = self.lab_values.clone();
+            let modified = self.modify_color_and_lab(&mut new_color, &mut new_lab);
 
-            self.modify_color_and_lab(&mut new_colors, &mut new_lab_values[random_index]);
+            // only the distances to the modified color need to be computed
+            let new_distances: Vec<Scalar> = self
+                .lab_values
+                .iter()
+                .enumerate()
+                .map(|(i, lab)| {
+                    if i == random_index {
+                        0.0
+                    } else {
+                        distance(distance_metric, lab, &new_lab)
+                    }
+                })
+                .collect();
 
-            let new_result = result.update(&new_lab_values, random_index);
+            let new_result = result.update(&matrix, random_index, &new_distances);
 
             let (score, new_score) = match self.parameters.opt_target {
                 OptimizationTarget::Mean => (
@@ -178,17 +306,22 @@ impl<R: Rng> SimulatedAnnealing<R> {
                 }
             };
 
-            if new_score > score {
-                result = new_result;
-                self.colors[random_index] = new_colors;
-                self.lab_values = new_lab_values;
+            // colors that violate the constraints (e.g. initial colors) are always replaced
+            let accept = if !modified {
+                false
+            } else if new_score > score || !admissible[random_index] {
+                true
             } else {
                 let bolzmann = Scalar::exp(-(score - new_score) / self.temperature);
-                if self.rng.gen::<Scalar>() <= bolzmann {
-                    result = new_result;
-                    self.colors[random_index] = new_colors;
-                    self.lab_values = new_lab_values;
-                }
+                self.rng.gen::<Scalar>() <= bolzmann
+            };
+
+            if accept {
+                result = new_result;
+                matrix.set_row(random_index, &new_distances);
+                self.colors[random_index] = new_color;
+                self.lab_values[random_index] = new_lab;
+                admissible[random_index] = true;
             }
 
             if iter % 5_000 == 0 {
@@ -217,18 +350,18 @@ impl<R: Rng> SimulatedAnnealing<R> {
 /// the sequence).
 ///
 /// See: <https://en.wikipedia.org/wiki/Farthest-first_traversal>
-pub fn rearrange_sequence(colors: &mut Vec<Color>, metric: DistanceMetric) {
+pub fn rearrange_sequence(colors: &mut [Color], metric: DistanceMetric) {
     let distance = |c1: &Color, c2: &Color| match metric {
         DistanceMetric::CIE76 => c1.distance_delta_e_cie76(c2),
         DistanceMetric::CIEDE2000 => c1.distance_delta_e_ciede2000(c2),
     };
 
     // vector where the i-th element contains the minimum distance to the colors from 0 to i-1.
-    let mut min_distances = vec![i32::max_value(); colors.len()];
+    let mut min_distances = vec![i32::MAX; colors.len()];
 
     for i in 1..colors.len() {
         let mut max_i = colors.len();
-        let mut max_d = i32::min_value();
+        let mut max_d = i32::MIN;
 
         for j in i..colors.len() {
             min_distances[j] =
@@ -245,6 +378,169 @@ pub fn rearrange_sequence(colors: &mut Vec<Color>, metric: DistanceMetric) {
     }
 }
 
+/// Spacing of the candidate grid in Lab space that is used by the greedy and hybrid algorithms.
+const LAB_GRID_STEP: Scalar = 5.0;
+
+/// The algorithm that is used to generate a set of distinct colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// Greedy farthest-point selection from a grid of candidate colors (fast and deterministic)
+    Greedy,
+
+    /// Simulated annealing, starting from random colors
+    Annealing,
+
+    /// Simulated annealing, starting from the greedy solution (restarts start from a k-means++
+    /// seeding of the candidate grid)
+    Hybrid,
+}
+
+type DistinctResult = (Vec<Color>, DistanceResult);
+
+/// The error that is returned if not enough colors can be found that satisfy the given
+/// `ColorConstraints`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsatisfiableConstraints;
+
+impl fmt::Display for UnsatisfiableConstraints {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "could not find colors that satisfy the given constraints"
+        )
+    }
+}
+
+impl std::error::Error for UnsatisfiableConstraints {}
+
+/// Search the whole color space by optimizing the mean distance, starting from the given colors.
+fn anneal_global(
+    initial_colors: &[Color],
+    distance_metric: DistanceMetric,
+    num_fixed_colors: usize,
+    constraints: ColorConstraints,
+    callback: &mut dyn FnMut(&IterationStatistics),
+) -> Vec<Color> {
+    let mut annealing = SimulatedAnnealing::new(
+        initial_colors,
+        SimulationParameters {
+            initial_temperature: 3.0,
+            cooling_rate: 0.95,
+            num_iterations: 100_000,
+            opt_target: OptimizationTarget::Mean,
+            opt_mode: OptimizationMode::Global,
+            distance_metric,
+            num_fixed_colors,
+            constraints,
+        },
+    );
+
+    annealing.run(callback);
+
+    annealing.colors
+}
+
+/// Refine the given colors with small modifications that maximize the minimal distance.
+fn anneal_local(
+    initial_colors: &[Color],
+    distance_metric: DistanceMetric,
+    num_fixed_colors: usize,
+    constraints: ColorConstraints,
+    callback: &mut dyn FnMut(&IterationStatistics),
+) -> DistinctResult {
+    let mut annealing = SimulatedAnnealing::new(
+        initial_colors,
+        SimulationParameters {
+            initial_temperature: 0.5,
+            cooling_rate: 0.98,
+            num_iterations: 200_000,
+            opt_target: OptimizationTarget::Min,
+            opt_mode: OptimizationMode::Local,
+            distance_metric,
+            num_fixed_colors,
+            constraints,
+        },
+    );
+
+    let result = annealing.run(callback);
+
+    (annealing.colors, result)
+}
+
+/// Run the given optimization `num_restarts` times in parallel and return the set of colors with
+/// the largest minimal distance. The optimization receives the index of the run. Only the first
+/// run reports its progress to the `callback`.
+fn best_of_restarts<F>(
+    num_restarts: usize,
+    callback: &(dyn Fn(&IterationStatistics) + Sync),
+    optimize: F,
+) -> Option<DistinctResult>
+where
+    F: Fn(usize, &mut dyn FnMut(&IterationStatistics)) -> Option<DistinctResult> + Sync,
+{
+    assert!(num_restarts > 0);
+
+    (0..num_restarts)
+        .into_par_iter()
+        .filter_map(|restart| {
+            let mut report = |stats: &IterationStatistics| {
+                if restart == 0 {
+                    callback(stats);
+                }
+            };
+            optimize(restart, &mut report)
+        })
+        .max_by(|(_, r1), (_, r2)| {
+            r1.min_closest_distance
+                .partial_cmp(&r2.min_closest_distance)
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+/// Generate a set of `count` visually distinct colors, starting with the given `fixed_colors`,
+/// using the given `algorithm`.
+///
+/// For the annealing-based algorithms, the optimization is restarted `num_restarts` times (in
+/// parallel) and the set with the largest minimal distance is returned. Only the first run reports
+/// its progress to the `callback`.
+///
+/// All generated colors satisfy the given `constraints`. An error is returned if not enough
+/// admissible colors can be found.
+pub fn distinct_colors_with(
+    algorithm: Algorithm,
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: Vec<Color>,
+    constraints: ColorConstraints,
+    num_restarts: usize,
+    callback: &(dyn Fn(&IterationStatistics) + Sync),
+) -> Result<DistinctResult, UnsatisfiableConstraints> {
+    match algorithm {
+        Algorithm::Greedy => {
+            greedy_distinct_colors(count, distance_metric, fixed_colors, constraints)
+        }
+        Algorithm::Annealing => annealing_distinct_colors(
+            count,
+            distance_metric,
+            fixed_colors,
+            constraints,
+            num_restarts,
+            callback,
+        ),
+        Algorithm::Hybrid => hybrid_distinct_colors(
+            count,
+            distance_metric,
+            fixed_colors,
+            constraints,
+            num_restarts,
+            callback,
+        ),
+    }
+}
+
+/// Generate a set of `count` visually distinct colors with simulated annealing, starting with the
+/// given `fixed_colors`. See `distinct_colors_with` for constraints, other algorithms and parallel
+/// restarts.
 pub fn distinct_colors(
     count: usize,
     distance_metric: DistanceMetric,
@@ -261,95 +557,418 @@ pub fn distinct_colors(
         colors.push(random::strategies::UniformRGB.generate());
     }
 
-    let mut annealing = SimulatedAnnealing::new(
+    let colors = anneal_global(
         &colors,
-        SimulationParameters {
-            initial_temperature: 3.0,
-            cooling_rate: 0.95,
-            num_iterations: 100_000,
-            opt_target: OptimizationTarget::Mean,
-            opt_mode: OptimizationMode::Global,
+        distance_metric,
+        num_fixed_colors,
+        ColorConstraints::default(),
+        callback,
+    );
+
+    anneal_local(
+        &colors,
+        distance_metric,
+        num_fixed_colors,
+        ColorConstraints::default(),
+        callback,
+    )
+}
+
+/// Generate a set of `count` visually distinct colors with simulated annealing, starting from
+/// random colors within the admissible region.
+fn annealing_distinct_colors(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: Vec<Color>,
+    constraints: ColorConstraints,
+    num_restarts: usize,
+    callback: &(dyn Fn(&IterationStatistics) + Sync),
+) -> Result<DistinctResult, UnsatisfiableConstraints> {
+    assert!(count > 1);
+    assert!(fixed_colors.len() <= count);
+
+    let num_fixed_colors = fixed_colors.len();
+
+    let best = best_of_restarts(num_restarts, callback, |_, report| {
+        let mut rng = thread_rng();
+        let mut colors = fixed_colors.clone();
+        for _ in num_fixed_colors..count {
+            colors.push(constraints.generate_with(&mut rng)?);
+        }
+
+        let colors = anneal_global(
+            &colors,
+            distance_metric,
+            num_fixed_colors,
+            constraints.clone(),
+            report,
+        );
+
+        Some(anneal_local(
+            &colors,
+            distance_metric,
+            num_fixed_colors,
+            constraints.clone(),
+            report,
+        ))
+    });
+
+    best.ok_or(UnsatisfiableConstraints)
+}
+
+/// Generate a set of `count` visually distinct colors with simulated annealing. The first run
+/// starts from the (deterministic) result of the greedy farthest-point selection. Further restarts
+/// start from a k-means++ seeding of the same candidate grid, such that they explore different
+/// solutions.
+fn hybrid_distinct_colors(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: Vec<Color>,
+    constraints: ColorConstraints,
+    num_restarts: usize,
+    callback: &(dyn Fn(&IterationStatistics) + Sync),
+) -> Result<DistinctResult, UnsatisfiableConstraints> {
+    assert!(count > 1);
+    assert!(fixed_colors.len() <= count);
+
+    let num_fixed_colors = fixed_colors.len();
+    let candidates = candidate_grid(&constraints);
+
+    let best = best_of_restarts(num_restarts, callback, |restart, report| {
+        let initial_colors = if restart == 0 {
+            greedy_selection(count, distance_metric, &fixed_colors, candidates.clone())?
+        } else {
+            kmeans_pp_selection(
+                count,
+                distance_metric,
+                &fixed_colors,
+                candidates.clone(),
+                &mut thread_rng(),
+            )?
+        };
+
+        let colors = anneal_global(
+            &initial_colors,
             distance_metric,
             num_fixed_colors,
+            constraints.clone(),
+            report,
+        );
+
+        Some(anneal_local(
+            &colors,
+            distance_metric,
+            num_fixed_colors,
+            constraints.clone(),
+            report,
+        ))
+    });
+
+    best.ok_or(UnsatisfiableConstraints)
+}
+
+/// Generate a set of `count` visually distinct colors by greedy farthest-point selection: starting
+/// with the fixed colors, the candidate with the largest distance to all previously selected
+/// colors is added until the set is complete. The candidates are taken from a regular grid in Lab
+/// space, restricted to the sRGB gamut and the admissible region. The result is deterministic.
+///
+/// See: <https://en.wikipedia.org/wiki/Farthest-first_traversal>
+fn greedy_distinct_colors(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: Vec<Color>,
+    constraints: ColorConstraints,
+) -> Result<DistinctResult, UnsatisfiableConstraints> {
+    assert!(count > 1);
+    assert!(fixed_colors.len() <= count);
+
+    let num_fixed_colors = fixed_colors.len();
+
+    let candidates = candidate_grid(&constraints);
+    let colors = greedy_selection(count, distance_metric, &fixed_colors, candidates)
+        .ok_or(UnsatisfiableConstraints)?;
+
+    let lab_values: Vec<Lab> = colors.iter().map(|c| c.to_lab()).collect();
+    let matrix = DistanceMatrix::new(&lab_values, distance_metric);
+    let result = DistanceResult::new(&matrix, distance_metric, num_fixed_colors);
+    Ok((colors, result))
+}
+
+/// The index of the first largest element.
+fn argmax(values: &[Scalar]) -> usize {
+    let mut max_index = 0;
+    for (i, value) in values.iter().enumerate() {
+        if *value > values[max_index] {
+            max_index = i;
+        }
+    }
+    max_index
+}
+
+/// All admissible colors on a regular grid in Lab space that lie within the sRGB gamut.
+fn candidate_grid(constraints: &ColorConstraints) -> Vec<(Color, Lab)> {
+    let steps = |min: Scalar, max: Scalar| {
+        let n = ((max - min) / LAB_GRID_STEP).floor() as usize;
+        (0..=n).map(move |i| min + i as Scalar * LAB_GRID_STEP)
+    };
+
+    let mut candidates = vec![];
+    for l in steps(0.0, 100.0) {
+        for a in steps(-125.0, 125.0) {
+            for b in steps(-125.0, 125.0) {
+                let lab = Lab::with_alpha(l, a, b, 1.0);
+                let color = Color::from(&lab);
+
+                // out-of-gamut colors are clipped to the sRGB gamut when converted to a `Color`
+                let clipped_lab = color.to_lab();
+                if delta_e::cie76(&lab, &clipped_lab) > 0.1 {
+                    continue;
+                }
+
+                if constraints.is_satisfied_lab(&color, &clipped_lab) {
+                    candidates.push((color, clipped_lab));
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Select `count` colors (including the fixed colors) from the given candidates. Starting with the
+/// fixed colors, `choose` picks the next candidate, given the minimal distance of each candidate
+/// to the colors that have been selected so far (`None` if no color has been selected yet).
+/// Returns `None` if there are not enough candidates.
+fn select_candidates<F>(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: &[Color],
+    mut candidates: Vec<(Color, Lab)>,
+    mut choose: F,
+) -> Option<Vec<Color>>
+where
+    F: FnMut(&[(Color, Lab)], Option<&[Scalar]>) -> usize,
+{
+    if candidates.len() < count - fixed_colors.len() {
+        return None;
+    }
+
+    // the minimal distance of each candidate to the selected colors
+    let mut min_distances = vec![Scalar::MAX; candidates.len()];
+
+    let update_min_distances = |candidates: &[(Color, Lab)],
+                                min_distances: &mut [Scalar],
+                                lab: &Lab| {
+        for ((_, candidate_lab), min_distance) in candidates.iter().zip(min_distances.iter_mut()) {
+            *min_distance = min_distance.min(distance(distance_metric, candidate_lab, lab));
+        }
+    };
+
+    let mut colors = fixed_colors.to_vec();
+    for color in fixed_colors {
+        update_min_distances(&candidates, &mut min_distances, &color.to_lab());
+    }
+
+    while colors.len() < count {
+        let chosen = if colors.is_empty() {
+            choose(&candidates, None)
+        } else {
+            choose(&candidates, Some(&min_distances))
+        };
+
+        let (color, lab) = candidates.swap_remove(chosen);
+        min_distances.swap_remove(chosen);
+
+        update_min_distances(&candidates, &mut min_distances, &lab);
+        colors.push(color);
+    }
+
+    Some(colors)
+}
+
+/// Select `count` colors (including the fixed colors) by farthest-point traversal of the
+/// candidates: the next color is always the candidate with the largest distance to all selected
+/// colors. Returns `None` if there are not enough candidates.
+fn greedy_selection(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: &[Color],
+    candidates: Vec<(Color, Lab)>,
+) -> Option<Vec<Color>> {
+    select_candidates(
+        count,
+        distance_metric,
+        fixed_colors,
+        candidates,
+        |candidates, min_distances| match min_distances {
+            Some(min_distances) => argmax(min_distances),
+            None => {
+                // start with the candidate that is farthest away from a neutral gray
+                let gray = Lab::with_alpha(50.0, 0.0, 0.0, 1.0);
+                let distances_to_gray: Vec<Scalar> = candidates
+                    .iter()
+                    .map(|(_, lab)| distance(distance_metric, lab, &gray))
+                    .collect();
+                argmax(&distances_to_gray)
+            }
         },
-    );
+    )
+}
 
-    annealing.run(callback);
+/// Select `count` colors (including the fixed colors) by k-means++ seeding: the next color is a
+/// random candidate, chosen with a probability proportional to the squared distance to the
+/// closest selected color. Returns `None` if there are not enough candidates.
+///
+/// See: <https://en.wikipedia.org/wiki/K-means%2B%2B>
+fn kmeans_pp_selection(
+    count: usize,
+    distance_metric: DistanceMetric,
+    fixed_colors: &[Color],
+    candidates: Vec<(Color, Lab)>,
+    rng: &mut dyn RngCore,
+) -> Option<Vec<Color>> {
+    select_candidates(
+        count,
+        distance_metric,
+        fixed_colors,
+        candidates,
+        |candidates, min_distances| match min_distances {
+            Some(min_distances) => {
+                let total: Scalar = min_distances.iter().map(|d| d * d).sum();
+                let mut threshold = rng.gen::<Scalar>() * total;
+                for (i, d) in min_distances.iter().enumerate() {
+                    threshold -= d * d;
+                    if threshold < 0.0 {
+                        return i;
+                    }
+                }
+                // only reached due to rounding errors (or if all distances are zero)
+                argmax(min_distances)
+            }
+            None => rng.gen_range(0..candidates.len()),
+        },
+    )
+}
 
-    annealing.parameters.initial_temperature = 0.5;
-    annealing.parameters.cooling_rate = 0.98;
-    annealing.parameters.num_iterations = 200_000;
-    annealing.parameters.opt_target = OptimizationTarget::Min;
-    annealing.parameters.opt_mode = OptimizationMode::Local;
+fn distance(distance_metric: DistanceMetric, a: &Lab, b: &Lab) -> Scalar {
+    match distance_metric {
+        DistanceMetric::CIE76 => delta_e::cie76(a, b),
+        DistanceMetric::CIEDE2000 => delta_e::ciede2000(a, b),
+    }
+}
 
-    let result = annealing.run(callback);
+/// The (symmetric) matrix of mutual distances between all colors. It is cached during the
+/// optimization such that only the distances to a modified color need to be recomputed.
+struct DistanceMatrix {
+    size: usize,
+    distances: Vec<Scalar>,
+}
 
-    (annealing.colors, result)
+impl DistanceMatrix {
+    fn new(lab_values: &[Lab], distance_metric: DistanceMetric) -> Self {
+        let size = lab_values.len();
+        let mut matrix = DistanceMatrix {
+            size,
+            distances: vec![0.0; size * size],
+        };
+
+        for i in 0..size {
+            for j in (i + 1)..size {
+                let dist = distance(distance_metric, &lab_values[i], &lab_values[j]);
+                matrix.distances[i * size + j] = dist;
+                matrix.distances[j * size + i] = dist;
+            }
+        }
+
+        matrix
+    }
+
+    fn row(&self, color: usize) -> &[Scalar] {
+        &self.distances[color * self.size..(color + 1) * self.size]
+    }
+
+    fn set_row(&mut self, color: usize, distances: &[Scalar]) {
+        for (i, dist) in distances.iter().enumerate() {
+            self.distances[color * self.size + i] = *dist;
+            self.distances[i * self.size + color] = *dist;
+        }
+    }
+}
+
+/// Find the closest distance and the index of the nearest neighbor in a row of the distance
+/// matrix.
+fn nearest_neighbor(distances: &[Scalar], color: usize) -> (Scalar, usize) {
+    let mut closest = (Scalar::MAX, usize::MAX);
+    for (i, dist) in distances.iter().enumerate() {
+        if i != color && *dist < closest.0 {
+            closest = (*dist, i);
+        }
+    }
+    closest
 }
 
 impl DistanceResult {
-    fn new(lab_values: &[Lab], distance_metric: DistanceMetric, num_fixed_colors: usize) -> Self {
+    fn new(
+        matrix: &DistanceMatrix,
+        distance_metric: DistanceMetric,
+        num_fixed_colors: usize,
+    ) -> Self {
         let mut result = DistanceResult {
-            closest_distances: vec![(scalar::MAX, std::usize::MAX); lab_values.len()],
-            closest_pair: (std::usize::MAX, std::usize::MAX),
+            closest_distances: (0..matrix.size)
+                .map(|i| nearest_neighbor(matrix.row(i), i))
+                .collect(),
+            closest_pair: (usize::MAX, usize::MAX),
             mean_closest_distance: 0.0,
-            min_closest_distance: scalar::MAX,
+            min_closest_distance: Scalar::MAX,
             distance_metric,
             num_fixed_colors,
         };
-
-        for i in 0..lab_values.len() {
-            result.update_distances(lab_values, i, false);
-        }
         result.update_totals();
 
         result
     }
 
-    fn update(&self, lab_values: &[Lab], changed_color: usize) -> Self {
+    /// Compute the result after `changed_color` has been modified. `new_distances` contains the
+    /// distances from the modified color to all other colors while `matrix` still holds the old
+    /// distances.
+    fn update(
+        &self,
+        matrix: &DistanceMatrix,
+        changed_color: usize,
+        new_distances: &[Scalar],
+    ) -> Self {
         let mut result = self.clone();
-        result.update_distances(lab_values, changed_color, true);
-        result.update_totals();
-        result
-    }
-
-    fn update_distances(&mut self, lab_values: &[Lab], color: usize, changed: bool) {
-        self.closest_distances[color] = (scalar::MAX, std::usize::MAX);
 
-        // we need to recalculate distances for nodes where the previous min dist was with
-        // changed_color but it's not anymore (potentially).
-        let mut to_recalc = Vec::with_capacity(lab_values.len());
-        let at_lab = lab_values[color].clone();
+        result.closest_distances[changed_color] = nearest_neighbor(new_distances, changed_color);
 
-        for (i, l) in lab_values.iter().enumerate() {
-            if i == color {
+        for (i, &dist) in new_distances.iter().enumerate() {
+            if i == changed_color {
                 continue;
             }
 
-            let dist = self.distance(l, &at_lab);
-
             if dist < self.closest_distances[i].0 {
-                self.closest_distances[i] = (dist, color);
-            } else if changed && self.closest_distances[i].1 == color {
-                // changed_color was the best before, but unfortunately we cannot say it now for
-                // sure because the distance between the two increased. Play it safe and just
-                // recalculate its distances.
-                to_recalc.push(i);
-            }
-
-            if dist < self.closest_distances[color].0 {
-                self.closest_distances[color] = (dist, i);
+                result.closest_distances[i] = (dist, changed_color);
+            } else if self.closest_distances[i].1 == changed_color {
+                // changed_color was the nearest neighbor before, but it might not be anymore
+                // because the distance between the two increased. Search the row again, using
+                // the cached distances for all other colors.
+                let mut closest = (dist, changed_color);
+                for (j, &dist_j) in matrix.row(i).iter().enumerate() {
+                    if j != i && j != changed_color && dist_j < closest.0 {
+                        closest = (dist_j, j);
+                    }
+                }
+                result.closest_distances[i] = closest;
             }
         }
 
-        for i in to_recalc {
-            self.update_distances(lab_values, i, false);
-        }
+        result.update_totals();
+        result
     }
 
     fn update_totals(&mut self) {
         self.mean_closest_distance = 0.0;
-        self.min_closest_distance = scalar::MAX;
+        self.min_closest_distance = Scalar::MAX;
 
         let mut closest_pair_set = false;
 
@@ -376,21 +995,17 @@ impl DistanceResult {
         self.mean_closest_distance /=
             (self.closest_distances.len() - self.num_fixed_colors) as Scalar;
     }
-
-    fn distance(&self, a: &Lab, b: &Lab) -> Scalar {
-        match self.distance_metric {
-            DistanceMetric::CIE76 => delta_e::cie76(a, b),
-            DistanceMetric::CIEDE2000 => delta_e::ciede2000(a, b),
-        }
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        rearrange_sequence, DistanceMetric, OptimizationMode, OptimizationTarget,
-        SimulatedAnnealing, SimulationParameters,
+        candidate_grid, distinct_colors_with, greedy_distinct_colors, kmeans_pp_selection,
+        rearrange_sequence, Algorithm, ColorConstraints, DistanceMatrix, DistanceMetric,
+        DistanceResult, OptimizationMode, OptimizationTarget, SimulatedAnnealing,
+        SimulationParameters, UnsatisfiableConstraints,
     };
+    use crate::random::{strategies, RandomizationStrategy};
     use crate::Color;
 
     use rand::prelude::*;
@@ -434,6 +1049,7 @@ mod tests {
                 opt_mode: OptimizationMode::Local,
                 distance_metric: DistanceMetric::CIE76,
                 num_fixed_colors: 3,
+                constraints: ColorConstraints::default(),
             },
             Xoshiro256StarStar::seed_from_u64(21),
         );
@@ -459,6 +1075,7 @@ mod tests {
                 opt_mode: OptimizationMode::Local,
                 distance_metric: DistanceMetric::CIE76,
                 num_fixed_colors: 1,
+                constraints: ColorConstraints::default(),
             },
             Xoshiro256StarStar::seed_from_u64(42),
         );
@@ -466,4 +1083,180 @@ mod tests {
 
         assert_eq!(sim.get_colors()[0], Color::red());
     }
+
+    #[test]
+    fn test_color_constraints() {
+        let constraints = ColorConstraints {
+            excluded_hues: vec![(330.0, 60.0)],
+            ..ColorConstraints::default()
+        };
+        assert!(!constraints.is_satisfied(&Color::red()));
+        assert!(constraints.is_satisfied(&Color::blue()));
+
+        let constraints = ColorConstraints {
+            min_lightness: 20.0,
+            max_lightness: 80.0,
+            ..ColorConstraints::default()
+        };
+        assert!(!constraints.is_satisfied(&Color::black()));
+        assert!(!constraints.is_satisfied(&Color::white()));
+        assert!(constraints.is_satisfied(&Color::gray()));
+
+        let constraints = ColorConstraints {
+            min_chroma: 10.0,
+            ..ColorConstraints::default()
+        };
+        assert!(!constraints.is_satisfied(&Color::gray()));
+        assert!(constraints.is_satisfied(&Color::red()));
+
+        let constraints = ColorConstraints {
+            background: Some((Color::black(), 4.5)),
+            ..ColorConstraints::default()
+        };
+        assert!(!constraints.is_satisfied(&Color::navy()));
+        assert!(constraints.is_satisfied(&Color::yellow()));
+    }
+
+    #[test]
+    fn test_distinct_with_constraints() {
+        let colors = [Color::white(), Color::gray(), Color::black()];
+        let constraints = ColorConstraints {
+            min_lightness: 40.0,
+            min_chroma: 20.0,
+            background: Some((Color::from_rgb(20, 20, 20), 3.0)),
+            ..ColorConstraints::default()
+        };
+
+        let mut sim = SimulatedAnnealing::with_rng(
+            &colors,
+            SimulationParameters {
+                initial_temperature: 3.0,
+                cooling_rate: 0.95,
+                num_iterations: 1000,
+                opt_target: OptimizationTarget::Mean,
+                opt_mode: OptimizationMode::Global,
+                distance_metric: DistanceMetric::CIE76,
+                num_fixed_colors: 0,
+                constraints: constraints.clone(),
+            },
+            Xoshiro256StarStar::seed_from_u64(7),
+        );
+        sim.run(&mut |_| {});
+
+        for color in sim.get_colors() {
+            assert!(constraints.is_satisfied(&color));
+        }
+    }
+
+    #[test]
+    fn test_incremental_distance_updates() {
+        let mut rng = Xoshiro256StarStar::seed_from_u64(3);
+        let colors: Vec<Color> = (0..12)
+            .map(|_| strategies::UniformRGB.generate_with(&mut rng))
+            .collect();
+
+        for opt_target in [OptimizationTarget::Mean, OptimizationTarget::Min] {
+            let mut sim = SimulatedAnnealing::with_rng(
+                &colors,
+                SimulationParameters {
+                    initial_temperature: 3.0,
+                    cooling_rate: 0.95,
+                    num_iterations: 2000,
+                    opt_target,
+                    opt_mode: OptimizationMode::Global,
+                    distance_metric: DistanceMetric::CIEDE2000,
+                    num_fixed_colors: 2,
+                    constraints: ColorConstraints::default(),
+                },
+                Xoshiro256StarStar::seed_from_u64(5),
+            );
+            let result = sim.run(&mut |_| {});
+
+            let matrix = DistanceMatrix::new(&sim.lab_values, DistanceMetric::CIEDE2000);
+            let expected = DistanceResult::new(&matrix, DistanceMetric::CIEDE2000, 2);
+
+            for (actual, expected) in result
+                .closest_distances
+                .iter()
+                .zip(expected.closest_distances.iter())
+            {
+                assert!((actual.0 - expected.0).abs() < 1e-9);
+            }
+            assert!((result.min_closest_distance - expected.min_closest_distance).abs() < 1e-9);
+            assert!((result.mean_closest_distance - expected.mean_closest_distance).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_greedy_distinct_colors() {
+        let constraints = ColorConstraints {
+            min_lightness: 30.0,
+            ..ColorConstraints::default()
+        };
+
+        let (colors, result) = greedy_distinct_colors(
+            5,
+            DistanceMetric::CIE76,
+            vec![Color::red()],
+            constraints.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(colors.len(), 5);
+        assert_eq!(colors[0], Color::red());
+        assert!(colors[1..].iter().all(|c| constraints.is_satisfied(c)));
+        assert!(result.min_closest_distance > 50.0);
+
+        let (colors_again, _) =
+            greedy_distinct_colors(5, DistanceMetric::CIE76, vec![Color::red()], constraints)
+                .unwrap();
+        assert_eq!(colors, colors_again);
+    }
+
+    #[test]
+    fn test_kmeans_pp_selection() {
+        let constraints = ColorConstraints {
+            max_chroma: 50.0,
+            ..ColorConstraints::default()
+        };
+        let mut rng = Xoshiro256StarStar::seed_from_u64(1);
+
+        let colors = kmeans_pp_selection(
+            6,
+            DistanceMetric::CIE76,
+            &[Color::white()],
+            candidate_grid(&constraints),
+            &mut rng,
+        )
+        .unwrap();
+
+        assert_eq!(colors.len(), 6);
+        assert_eq!(colors[0], Color::white());
+        assert!(colors[1..].iter().all(|c| constraints.is_satisfied(c)));
+        for (i, c1) in colors.iter().enumerate() {
+            assert!(colors[i + 1..].iter().all(|c2| c1 != c2));
+        }
+    }
+
+    #[test]
+    fn test_unsatisfiable_constraints() {
+        let constraints = ColorConstraints {
+            min_lightness: 60.0,
+            max_lightness: 40.0,
+            ..ColorConstraints::default()
+        };
+
+        for algorithm in [Algorithm::Greedy, Algorithm::Annealing, Algorithm::Hybrid] {
+            let result = distinct_colors_with(
+                algorithm,
+                3,
+                DistanceMetric::CIE76,
+                vec![],
+                constraints.clone(),
+                1,
+                &|_| {},
+            );
+            assert!(matches!(result, Err(UnsatisfiableConstraints)));
+        }
+    }
 }
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use crate::helper::Fraction;
+use crate::types::Scalar;
+
+/// An easing function that maps the interpolation parameter between two color stops of a
+/// `ColorScale` to a new value between 0.0 and 1.0.
+///
+/// See: <https://www.w3.org/TR/css-easing-1/>
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    /// No easing (`t`)
+    Linear,
+
+    /// Smooth Hermite interpolation (`3t² - 2t³`)
+    Smoothstep,
+
+    /// A cubic Bézier curve from (0, 0) to (1, 1), defined by the two control points
+    /// (x1, y1) and (x2, y2). The x coordinates must lie between 0.0 and 1.0.
+    CubicBezier(Scalar, Scalar, Scalar, Scalar),
+}
+
+impl Easing {
+    /// The CSS `ease` timing function.
+    pub const EASE: Easing = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+
+    /// The CSS `ease-in` timing function.
+    pub const EASE_IN: Easing = Easing::CubicBezier(0.42, 0.0, 1.0, 1.0);
+
+    /// The CSS `ease-out` timing function.
+    pub const EASE_OUT: Easing = Easing::CubicBezier(0.0, 0.0, 0.58, 1.0);
+
+    /// The CSS `ease-in-out` timing function.
+    pub const EASE_IN_OUT: Easing = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+
+    /// Apply the easing function to the given fraction.
+    pub fn apply(&self, fraction: Fraction) -> Fraction {
+        let t = fraction.value();
+
+        match *self {
+            Easing::Linear => fraction,
+            Easing::Smoothstep => Fraction::from(t * t * (3.0 - 2.0 * t)),
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                let s = solve_bezier(x1, x2, t);
+                Fraction::from(bezier(y1, y2, s))
+            }
+        }
+    }
+}
+
+/// One coordinate of a cubic Bézier curve with end points 0.0 and 1.0 and the given control
+/// point coordinates, evaluated at the curve parameter `s`.
+fn bezier(p1: Scalar, p2: Scalar, s: Scalar) -> Scalar {
+    let u = 1.0 - s;
+    3.0 * u * u * s * p1 + 3.0 * u * s * s * p2 + s * s * s
+}
+
+fn bezier_derivative(p1: Scalar, p2: Scalar, s: Scalar) -> Scalar {
+    let u = 1.0 - s;
+    3.0 * u * u * p1 + 6.0 * u * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+}
+
+/// Find the curve parameter `s` for which the x coordinate of the curve equals `x`. The x
+/// coordinate is monotonic in `s` because the control points lie within [0, 1].
+fn solve_bezier(x1: Scalar, x2: Scalar, x: Scalar) -> Scalar {
+    const EPSILON: Scalar = 1e-7;
+
+    // Newton's method usually converges within a few steps …
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < EPSILON {
+            return s;
+        }
+        let derivative = bezier_derivative(x1, x2, s);
+        if derivative.abs() < EPSILON {
+            break;
+        }
+        s -= error / derivative;
+    }
+
+    // … otherwise, fall back to bisection
+    let (mut lower, mut upper) = (0.0, 1.0);
+    s = x;
+    while upper - lower > EPSILON {
+        if bezier(x1, x2, s) < x {
+            lower = s;
+        } else {
+            upper = s;
+        }
+        s = (lower + upper) / 2.0;
+    }
+    s
+}
+
+impl FromStr for Easing {
+    type Err = &'static str;
+
+    /// Parse an easing function from its CSS name (`linear`, `ease`, `ease-in`, `ease-out`,
+    /// `ease-in-out`, `cubic-bezier(x1, y1, x2, y2)`) or `smoothstep`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "linear" => Ok(Easing::Linear),
+            "ease" => Ok(Easing::EASE),
+            "ease-in" => Ok(Easing::EASE_IN),
+            "ease-out" => Ok(Easing::EASE_OUT),
+            "ease-in-out" => Ok(Easing::EASE_IN_OUT),
+            "smoothstep" => Ok(Easing::Smoothstep),
+            _ => {
+                let arguments = s
+                    .strip_prefix("cubic-bezier(")
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .ok_or("invalid easing function")?;
+
+                let values = arguments
+                    .split(',')
+                    .map(|v| v.trim().parse::<Scalar>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| "invalid cubic-bezier() arguments")?;
+
+                match values[..] {
+                    [x1, y1, x2, y2] if (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2) => {
+                        Ok(Easing::CubicBezier(x1, y1, x2, y2))
+                    }
+                    _ => Err("invalid cubic-bezier() arguments"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn apply(easing: Easing, t: Scalar) -> Scalar {
+        easing.apply(Fraction::from(t)).value()
+    }
+
+    #[test]
+    fn end_points_are_fixed() {
+        for easing in [
+            Easing::Linear,
+            Easing::Smoothstep,
+            Easing::EASE,
+            Easing::EASE_IN,
+            Easing::EASE_OUT,
+            Easing::EASE_IN_OUT,
+        ] {
+            assert_relative_eq!(0.0, apply(easing, 0.0), epsilon = 1e-6);
+            assert_relative_eq!(1.0, apply(easing, 1.0), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn easing_values() {
+        assert_eq!(0.3, apply(Easing::Linear, 0.3));
+        assert_relative_eq!(0.5, apply(Easing::Smoothstep, 0.5));
+        assert_relative_eq!(0.5, apply(Easing::EASE_IN_OUT, 0.5), epsilon = 1e-6);
+        assert!(apply(Easing::EASE_IN, 0.5) < 0.5);
+        assert!(apply(Easing::EASE_OUT, 0.5) > 0.5);
+
+        // a cubic Bézier curve with control points on the diagonal is linear
+        let diagonal = Easing::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        assert_relative_eq!(0.3, apply(diagonal, 0.3), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn parse_easing() {
+        assert_eq!(Ok(Easing::Linear), "linear".parse());
+        assert_eq!(Ok(Easing::EASE_IN_OUT), "ease-in-out".parse());
+        assert_eq!(Ok(Easing::Smoothstep), "Smoothstep".parse());
+        assert_eq!(
+            Ok(Easing::CubicBezier(0.1, -0.5, 0.9, 1.5)),
+            "cubic-bezier(0.1, -0.5, 0.9, 1.5)".parse()
+        );
+
+        assert!("cubic-bezier(0.1, 0.2, 0.3)".parse::<Easing>().is_err());
+        assert!("cubic-bezier(1.1, 0.2, 0.3, 0.4)"
+            .parse::<Easing>()
+            .is_err());
+        assert!("bounce".parse::<Easing>().is_err());
+    }
+}
@@ -1,7 +1,4 @@
-use std::{
-    cmp::Ordering,
-    fmt::{self, Display},
-};
+use std::fmt::{self, Display};
 
 use crate::types::Scalar;
 
@@ -37,18 +34,89 @@ pub fn interpolate(a: Scalar, b: Scalar, fraction: Fraction) -> Scalar {
     a + fraction.value() * (b - a)
 }
 
-/// Linearly interpolate between two angles. Always take the shortest path
-/// along the circle.
-pub fn interpolate_angle(a: Scalar, b: Scalar, fraction: Fraction) -> Scalar {
-    let paths = [(a, b), (a, b + 360.0), (a + 360.0, b)];
+/// Linearly interpolate between two values with premultiplied alpha: both values are multiplied
+/// by their alpha value before interpolating and the result is divided by the interpolated alpha.
+///
+/// See: <https://www.w3.org/TR/css-color-4/#interpolation-alpha>
+pub fn interpolate_premultiplied(
+    a: Scalar,
+    alpha_a: Scalar,
+    b: Scalar,
+    alpha_b: Scalar,
+    fraction: Fraction,
+) -> Scalar {
+    let alpha = interpolate(alpha_a, alpha_b, fraction);
+    if alpha == 0.0 {
+        interpolate(a, b, fraction)
+    } else {
+        interpolate(a * alpha_a, b * alpha_b, fraction) / alpha
+    }
+}
+
+/// The path along the hue circle that is taken when interpolating between two hue angles.
+///
+/// See: <https://www.w3.org/TR/css-color-4/#hue-interpolation>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HueInterpolation {
+    /// Take the shorter path (at most 180°)
+    Shorter,
 
-    let dist = |&(x, y): &(Scalar, Scalar)| (x - y).abs();
-    let shortest = paths
-        .iter()
-        .min_by(|p1, p2| dist(p1).partial_cmp(&dist(p2)).unwrap_or(Ordering::Less))
-        .unwrap();
+    /// Take the longer path (at least 180°)
+    Longer,
 
-    mod_positive(interpolate(shortest.0, shortest.1, fraction), 360.0)
+    /// Always go counter-clockwise, i.e. increase the hue angle
+    Increasing,
+
+    /// Always go clockwise, i.e. decrease the hue angle
+    Decreasing,
+}
+
+impl Default for HueInterpolation {
+    fn default() -> Self {
+        HueInterpolation::Shorter
+    }
+}
+
+/// Linearly interpolate between two angles, taking the path along the circle
+/// that is given by the hue interpolation method.
+pub fn interpolate_angle(
+    a: Scalar,
+    b: Scalar,
+    fraction: Fraction,
+    method: HueInterpolation,
+) -> Scalar {
+    let mut a = mod_positive(a, 360.0);
+    let mut b = mod_positive(b, 360.0);
+    let diff = b - a;
+
+    match method {
+        HueInterpolation::Shorter => {
+            if diff > 180.0 {
+                a += 360.0;
+            } else if diff < -180.0 {
+                b += 360.0;
+            }
+        }
+        HueInterpolation::Longer => {
+            if 0.0 < diff && diff < 180.0 {
+                a += 360.0;
+            } else if -180.0 < diff && diff <= 0.0 {
+                b += 360.0;
+            }
+        }
+        HueInterpolation::Increasing => {
+            if b < a {
+                b += 360.0;
+            }
+        }
+        HueInterpolation::Decreasing => {
+            if a < b {
+                a += 360.0;
+            }
+        }
+    }
+
+    mod_positive(interpolate(a, b, fraction), 360.0)
 }
 
 // `format!`-style format strings only allow specifying a fixed floating
@@ -99,12 +167,44 @@ mod tests {
         assert_eq!(0.5, interpolate(0.0, 0.5, Fraction::from(1.1)));
     }
 
+    #[test]
+    fn test_interpolate_premultiplied() {
+        let half = Fraction::from(0.5);
+        assert_eq!(0.5, interpolate_premultiplied(0.0, 1.0, 1.0, 1.0, half));
+        assert_eq!(1.0, interpolate_premultiplied(0.0, 0.0, 1.0, 1.0, half));
+        assert_eq!(0.75, interpolate_premultiplied(0.0, 0.5, 1.0, 1.5, half));
+        assert_eq!(0.5, interpolate_premultiplied(0.0, 0.0, 1.0, 0.0, half));
+    }
+
     #[test]
     fn test_interpolate_angle() {
-        assert_eq!(15.0, interpolate_angle(0.0, 30.0, Fraction::from(0.5)));
-        assert_eq!(20.0, interpolate_angle(0.0, 100.0, Fraction::from(0.2)));
-        assert_eq!(0.0, interpolate_angle(10.0, 350.0, Fraction::from(0.5)));
-        assert_eq!(0.0, interpolate_angle(350.0, 10.0, Fraction::from(0.5)));
+        let shorter = |a, b, fraction| {
+            interpolate_angle(a, b, Fraction::from(fraction), HueInterpolation::Shorter)
+        };
+
+        assert_eq!(15.0, shorter(0.0, 30.0, 0.5));
+        assert_eq!(20.0, shorter(0.0, 100.0, 0.2));
+        assert_eq!(0.0, shorter(10.0, 350.0, 0.5));
+        assert_eq!(0.0, shorter(350.0, 10.0, 0.5));
+    }
+
+    #[test]
+    fn test_interpolate_angle_methods() {
+        use HueInterpolation::*;
+        let half = Fraction::from(0.5);
+
+        assert_eq!(195.0, interpolate_angle(0.0, 30.0, half, Longer));
+        assert_eq!(180.0, interpolate_angle(10.0, 350.0, half, Longer));
+        assert_eq!(180.0, interpolate_angle(350.0, 10.0, half, Longer));
+        assert_eq!(270.0, interpolate_angle(90.0, 90.0, half, Longer));
+
+        assert_eq!(15.0, interpolate_angle(0.0, 30.0, half, Increasing));
+        assert_eq!(180.0, interpolate_angle(10.0, 350.0, half, Increasing));
+        assert_eq!(0.0, interpolate_angle(350.0, 10.0, half, Increasing));
+
+        assert_eq!(195.0, interpolate_angle(0.0, 30.0, half, Decreasing));
+        assert_eq!(0.0, interpolate_angle(10.0, 350.0, half, Decreasing));
+        assert_eq!(180.0, interpolate_angle(350.0, 10.0, half, Decreasing));
     }
 
     #[test]
@@ -3,15 +3,21 @@ use std::fmt;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::all_consuming,
-    IResult,
+    error::context,
 };
 
 use crate::{
     colorspace::ColorSpace,
-    helper::{clamp, interpolate, interpolate_angle, MaxPrecision},
-    parser::{hue_angle, legacy_alpha, legacy_separator, modern_alpha, percentage},
+    helper::{
+        clamp, interpolate, interpolate_angle, interpolate_premultiplied, HueInterpolation,
+        MaxPrecision,
+    },
+    parser::{
+        closing_parenthesis, component, hue_angle, legacy_alpha, legacy_separator, modern_alpha,
+        percentage, IResult,
+    },
     types::{Hue, Scalar},
     Color, Format, Fraction,
 };
@@ -34,17 +40,43 @@ impl ColorSpace for HSLA {
     }
 
     fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        self.mix_with_hue(other, fraction, HueInterpolation::Shorter)
+    }
+
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
         // make sure that the hue is preserved when mixing with gray colors
         let self_hue = if self.s < 0.0001 { other.h } else { self.h };
         let other_hue = if other.s < 0.0001 { self.h } else { other.h };
 
         Self {
-            h: interpolate_angle(self_hue, other_hue, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
             s: interpolate(self.s, other.s, fraction),
             l: interpolate(self.l, other.l, fraction),
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // the hue of gray and fully transparent colors is ignored
+        let self_hue = if self.s < 0.0001 || self.alpha == 0.0 {
+            other.h
+        } else {
+            self.h
+        };
+        let other_hue = if other.s < 0.0001 || other.alpha == 0.0 {
+            self.h
+        } else {
+            other.h
+        };
+
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            s: interpolate_premultiplied(self.s, alpha_a, other.s, alpha_b, fraction),
+            l: interpolate_premultiplied(self.l, alpha_a, other.l, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl From<&Color> for HSLA {
@@ -114,6 +146,10 @@ impl HSLA {
     }
 }
 
+const HUE: &str = "expected hue (a number or an angle)";
+const SATURATION: &str = "expected saturation (a percentage)";
+const LIGHTNESS: &str = "expected lightness (a percentage)";
+
 pub(crate) fn parse_hsl_color(input: &str) -> IResult<&str, Color> {
     alt((
         all_consuming(parse_css_hsl),
@@ -124,14 +160,11 @@ pub(crate) fn parse_hsl_color(input: &str) -> IResult<&str, Color> {
 fn parse_legacy_hsl(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag("hsl("), tag("hsla(")))(input)?;
     let (input, _) = space0(input)?;
-    let (input, h) = hue_angle(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, s) = percentage(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, l) = percentage(input)?;
+    let (input, h) = context(HUE, hue_angle)(input)?;
+    let (input, s) = component(SATURATION, legacy_separator, percentage)(input)?;
+    let (input, l) = component(LIGHTNESS, legacy_separator, percentage)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_hsla(h, s, l, alpha);
     Ok((input, c))
@@ -140,14 +173,11 @@ fn parse_legacy_hsl(input: &str) -> IResult<&str, Color> {
 fn parse_css_hsl(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag_no_case("hsl("), tag_no_case("hsla(")))(input)?;
     let (input, _) = space0(input)?;
-    let (input, h) = hue_angle(input)?;
-    let (input, _) = space1(input)?;
-    let (input, s) = percentage(input)?;
-    let (input, _) = space1(input)?;
-    let (input, l) = percentage(input)?;
+    let (input, h) = context(HUE, hue_angle)(input)?;
+    let (input, s) = component(SATURATION, space1, percentage)(input)?;
+    let (input, l) = component(LIGHTNESS, space1, percentage)(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_hsla(h, s, l, alpha);
     Ok((input, c))
@@ -3,18 +3,21 @@ use std::fmt;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::{all_consuming, opt},
+    error::context,
     sequence::preceded,
-    IResult,
 };
 
 use crate::{
     colorspace::ColorSpace,
-    helper::{clamp, interpolate, interpolate_angle, MaxPrecision},
+    helper::{
+        clamp, interpolate, interpolate_angle, interpolate_premultiplied, HueInterpolation,
+        MaxPrecision,
+    },
     parser::{
-        css_color_function, hue_angle, legacy_alpha, legacy_separator, modern_alpha,
-        number_or_percentage, percentage,
+        closing_parenthesis, component, css_color_function, hue_angle, legacy_alpha,
+        legacy_separator, modern_alpha, number_or_percentage, percentage, IResult,
     },
     types::{Hue, Scalar},
     Color, Format, Fraction,
@@ -76,17 +79,43 @@ impl ColorSpace for HSVA {
     }
 
     fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        self.mix_with_hue(other, fraction, HueInterpolation::Shorter)
+    }
+
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
         // make sure that the hue is preserved when mixing with gray colors
         let self_hue = if self.s < 0.0001 { other.h } else { self.h };
         let other_hue = if other.s < 0.0001 { self.h } else { other.h };
 
         Self {
-            h: interpolate_angle(self_hue, other_hue, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
             s: interpolate(self.s, other.s, fraction),
             v: interpolate(self.v, other.v, fraction),
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // the hue of gray and fully transparent colors is ignored
+        let self_hue = if self.s < 0.0001 || self.alpha == 0.0 {
+            other.h
+        } else {
+            self.h
+        };
+        let other_hue = if other.s < 0.0001 || other.alpha == 0.0 {
+            self.h
+        } else {
+            other.h
+        };
+
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            s: interpolate_premultiplied(self.s, alpha_a, other.s, alpha_b, fraction),
+            v: interpolate_premultiplied(self.v, alpha_a, other.v, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl fmt::Display for HSVA {
@@ -134,6 +163,12 @@ impl HSVA {
     }
 }
 
+const HUE: &str = "expected hue (a number or an angle)";
+const SATURATION: &str = "expected saturation (a number or a percentage)";
+const VALUE: &str = "expected value (a number or a percentage)";
+const LEGACY_SATURATION: &str = "expected saturation (a percentage)";
+const LEGACY_VALUE: &str = "expected value (a percentage)";
+
 pub(crate) fn parse_hsv_color(input: &str) -> IResult<&str, Color> {
     alt((
         all_consuming(parse_css_hsv),
@@ -145,14 +180,11 @@ pub(crate) fn parse_hsv_color(input: &str) -> IResult<&str, Color> {
 fn parse_legacy_hsv(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag("hsv("), tag("hsva(")))(input)?;
     let (input, _) = space0(input)?;
-    let (input, h) = hue_angle(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, s) = percentage(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, v) = percentage(input)?;
+    let (input, h) = context(HUE, hue_angle)(input)?;
+    let (input, s) = component(LEGACY_SATURATION, legacy_separator, percentage)(input)?;
+    let (input, v) = component(LEGACY_VALUE, legacy_separator, percentage)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_hsva(h, s, v, alpha);
     Ok((input, c))
@@ -164,15 +196,12 @@ fn parse_legacy_hsv(input: &str) -> IResult<&str, Color> {
 fn parse_css_hsv(input: &str) -> IResult<&str, Color> {
     let (input, _) = tag_no_case("hsv(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, h) = hue_angle(input)?;
-    let (input, _) = space1(input)?;
+    let (input, h) = context(HUE, hue_angle)(input)?;
     // Percent reference range for S and V: 0% = 0, 100% = 1
-    let (input, s) = number_or_percentage(input, 1.0)?;
-    let (input, _) = space1(input)?;
-    let (input, v) = number_or_percentage(input, 1.0)?;
+    let (input, s) = component(SATURATION, space1, |i| number_or_percentage(i, 1.0))(input)?;
+    let (input, v) = component(VALUE, space1, |i| number_or_percentage(i, 1.0))(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_hsva(h, s, v, alpha);
     Ok((input, c))
@@ -186,12 +215,10 @@ fn parse_css_hsv(input: &str) -> IResult<&str, Color> {
 fn parse_hsv_color_space(input: &str) -> IResult<&str, Color> {
     fn hsv_components(input: &str) -> IResult<&str, Color> {
         // Optional angle units are allowed because they are not ambiguous.
-        let (input, h) = hue_angle(input)?;
-        let (input, _) = space1(input)?;
+        let (input, h) = component(HUE, space1, hue_angle)(input)?;
         // Percentages can be used with 0 to 1 values.
-        let (input, s) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, v) = number_or_percentage(input, 1.0)?;
+        let (input, s) = component(SATURATION, space1, |i| number_or_percentage(i, 1.0))(input)?;
+        let (input, v) = component(VALUE, space1, |i| number_or_percentage(i, 1.0))(input)?;
 
         let c = Color::from_hsv(h, s, v);
         Ok((input, c))
@@ -2,17 +2,20 @@ use std::fmt;
 
 use nom::{
     bytes::complete::tag_no_case,
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::all_consuming,
-    IResult,
+    error::context,
 };
 
 use crate::{
     colorspace::ColorSpace,
     format_css_alpha,
-    helper::{clamp, interpolate, interpolate_angle, MaxPrecision},
+    helper::{
+        clamp, interpolate, interpolate_angle, interpolate_premultiplied, HueInterpolation,
+        MaxPrecision,
+    },
     hsv::HSVA,
-    parser::{hue_angle, modern_alpha, percentage},
+    parser::{closing_parenthesis, component, hue_angle, modern_alpha, percentage, IResult},
     types::Scalar,
     Color, Format, Fraction,
 };
@@ -35,6 +38,10 @@ impl ColorSpace for HWBA {
     }
 
     fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        self.mix_with_hue(other, fraction, HueInterpolation::Shorter)
+    }
+
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
         // make sure that the hue is preserved when mixing with gray colors
         let self_hue = if (self.w + self.b) >= 1.0 {
             other.h
@@ -48,12 +55,34 @@ impl ColorSpace for HWBA {
         };
 
         Self {
-            h: interpolate_angle(self_hue, other_hue, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
             w: interpolate(self.w, other.w, fraction),
             b: interpolate(self.b, other.b, fraction),
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // the hue of gray and fully transparent colors is ignored
+        let self_hue = if (self.w + self.b) >= 1.0 || self.alpha == 0.0 {
+            other.h
+        } else {
+            self.h
+        };
+        let other_hue = if (other.w + other.b) >= 1.0 || other.alpha == 0.0 {
+            self.h
+        } else {
+            other.h
+        };
+
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            w: interpolate_premultiplied(self.w, alpha_a, other.w, alpha_b, fraction),
+            b: interpolate_premultiplied(self.b, alpha_a, other.b, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl From<&Color> for HWBA {
@@ -117,14 +146,11 @@ pub(crate) fn parse_hwb_color(input: &str) -> IResult<&str, Color> {
 fn parse_css_hwb(input: &str) -> IResult<&str, Color> {
     let (input, _) = tag_no_case("hwb(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, h) = hue_angle(input)?;
-    let (input, _) = space1(input)?;
-    let (input, w) = percentage(input)?;
-    let (input, _) = space1(input)?;
-    let (input, b) = percentage(input)?;
+    let (input, h) = context("expected hue (a number or an angle)", hue_angle)(input)?;
+    let (input, w) = component("expected whiteness (a percentage)", space1, percentage)(input)?;
+    let (input, b) = component("expected blackness (a percentage)", space1, percentage)(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_hwba(h, w, b, alpha);
     Ok((input, c))
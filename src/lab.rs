@@ -3,18 +3,19 @@ use std::fmt;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::{all_consuming, opt},
+    error::context,
     number::complete::double,
     sequence::preceded,
-    IResult,
 };
 
 use crate::{
     colorspace::ColorSpace,
-    helper::{interpolate, MaxPrecision},
+    helper::{interpolate, interpolate_premultiplied, HueInterpolation, MaxPrecision},
     parser::{
-        css_color_function, legacy_alpha, legacy_separator, modern_alpha, number_or_percentage,
+        closing_parenthesis, component, css_color_function, legacy_alpha, legacy_separator,
+        modern_alpha, number_or_percentage, IResult,
     },
     types::Scalar,
     xyz::XYZ,
@@ -46,6 +47,16 @@ impl ColorSpace for Lab {
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, _hue: HueInterpolation) -> Self {
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            l: interpolate_premultiplied(self.l, alpha_a, other.l, alpha_b, fraction),
+            a: interpolate_premultiplied(self.a, alpha_a, other.a, alpha_b, fraction),
+            b: interpolate_premultiplied(self.b, alpha_a, other.b, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl From<&Color> for Lab {
@@ -133,6 +144,13 @@ impl Lab {
     }
 }
 
+const LIGHTNESS: &str = "expected lightness (a number)";
+const A: &str = "expected a (a number)";
+const B: &str = "expected b (a number)";
+const CSS_LIGHTNESS: &str = "expected lightness (a number or a percentage)";
+const CSS_A: &str = "expected a (a number or a percentage)";
+const CSS_B: &str = "expected b (a number or a percentage)";
+
 pub(crate) fn parse_lab_color(input: &str) -> IResult<&str, Color> {
     alt((
         all_consuming(parse_css_lab65),
@@ -145,14 +163,11 @@ fn parse_legacy_lab(input: &str) -> IResult<&str, Color> {
     let (input, _) = opt(tag_no_case("cie"))(input)?;
     let (input, _) = tag_no_case("lab(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, l) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, a) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, b) = double(input)?;
+    let (input, l) = context(LIGHTNESS, double)(input)?;
+    let (input, a) = component(A, legacy_separator, double)(input)?;
+    let (input, b) = component(B, legacy_separator, double)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_lab(l, a, b, alpha);
 
@@ -174,15 +189,12 @@ fn parse_css_lab65(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag_no_case("lab65("), tag_no_case("lab-d65(")))(input)?;
     let (input, _) = space0(input)?;
     // Percent reference range for L: 0% = 0, 100% = 100
-    let (input, l) = number_or_percentage(input, 100.0)?;
-    let (input, _) = space1(input)?;
+    let (input, l) = context(CSS_LIGHTNESS, |i| number_or_percentage(i, 100.0))(input)?;
     // Percent reference range for a and b: -100% = -125, 100% = 125
-    let (input, a) = number_or_percentage(input, 125.0)?;
-    let (input, _) = space1(input)?;
-    let (input, b) = number_or_percentage(input, 125.0)?;
+    let (input, a) = component(CSS_A, space1, |i| number_or_percentage(i, 125.0))(input)?;
+    let (input, b) = component(CSS_B, space1, |i| number_or_percentage(i, 125.0))(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_lab(l, a, b, alpha);
 
@@ -197,11 +209,9 @@ fn parse_css_lab65(input: &str) -> IResult<&str, Color> {
 fn parse_lab_d65_color_space(input: &str) -> IResult<&str, Color> {
     fn lab_components(input: &str) -> IResult<&str, Color> {
         // Don't allow percentages because 100% = 1.0 for color space components.
-        let (input, l) = double(input)?;
-        let (input, _) = space1(input)?;
-        let (input, a) = double(input)?;
-        let (input, _) = space1(input)?;
-        let (input, b) = double(input)?;
+        let (input, l) = component(LIGHTNESS, space1, double)(input)?;
+        let (input, a) = component(A, space1, double)(input)?;
+        let (input, b) = component(B, space1, double)(input)?;
 
         let c = Color::from_lab(l, a, b, 1.0);
         Ok((input, c))
@@ -3,20 +3,23 @@ use std::fmt;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::{char, space0, space1},
+    character::complete::{space0, space1},
     combinator::{all_consuming, opt},
+    error::context,
     number::complete::double,
     sequence::preceded,
-    IResult,
 };
 
 use crate::{
     colorspace::ColorSpace,
-    helper::{interpolate, interpolate_angle, mod_positive, MaxPrecision},
+    helper::{
+        interpolate, interpolate_angle, interpolate_premultiplied, mod_positive, HueInterpolation,
+        MaxPrecision,
+    },
     lab::Lab,
     parser::{
-        css_color_function, hue_angle, legacy_alpha, legacy_separator, modern_alpha,
-        number_or_percentage,
+        closing_parenthesis, component, css_color_function, hue_angle, legacy_alpha,
+        legacy_separator, modern_alpha, number_or_percentage, IResult,
     },
     types::Scalar,
     Color, Format, Fraction,
@@ -40,6 +43,10 @@ impl ColorSpace for LCh {
     }
 
     fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        self.mix_with_hue(other, fraction, HueInterpolation::Shorter)
+    }
+
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
         // make sure that the hue is preserved when mixing with gray colors
         let self_hue = if self.c < 0.1 { other.h } else { self.h };
         let other_hue = if other.c < 0.1 { self.h } else { other.h };
@@ -47,15 +54,43 @@ impl ColorSpace for LCh {
         Self {
             l: interpolate(self.l, other.l, fraction),
             c: interpolate(self.c, other.c, fraction),
-            h: interpolate_angle(self_hue, other_hue, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // the hue of gray and fully transparent colors is ignored
+        let self_hue = if self.c < 0.1 || self.alpha == 0.0 {
+            other.h
+        } else {
+            self.h
+        };
+        let other_hue = if other.c < 0.1 || other.alpha == 0.0 {
+            self.h
+        } else {
+            other.h
+        };
+
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            l: interpolate_premultiplied(self.l, alpha_a, other.l, alpha_b, fraction),
+            c: interpolate_premultiplied(self.c, alpha_a, other.c, alpha_b, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl From<&Color> for LCh {
     fn from(color: &Color) -> Self {
-        let Lab { l, a, b, alpha } = Lab::from(color);
+        Self::from(&Lab::from(color))
+    }
+}
+
+impl From<&Lab> for LCh {
+    fn from(lab: &Lab) -> Self {
+        let &Lab { l, a, b, alpha } = lab;
 
         const RAD2DEG: Scalar = 180.0 / std::f64::consts::PI;
 
@@ -118,6 +153,12 @@ impl LCh {
     }
 }
 
+const LIGHTNESS: &str = "expected lightness (a number)";
+const CHROMA: &str = "expected chroma (a number)";
+const HUE: &str = "expected hue (a number or an angle)";
+const CSS_LIGHTNESS: &str = "expected lightness (a number or a percentage)";
+const CSS_CHROMA: &str = "expected chroma (a number or a percentage)";
+
 pub(crate) fn parse_lch_color(input: &str) -> IResult<&str, Color> {
     alt((
         all_consuming(parse_css_lch65),
@@ -130,14 +171,11 @@ fn parse_legacy_lch(input: &str) -> IResult<&str, Color> {
     let (input, _) = opt(tag_no_case("cie"))(input)?;
     let (input, _) = tag_no_case("lch(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, l) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, c) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, h) = hue_angle(input)?;
+    let (input, l) = context(LIGHTNESS, double)(input)?;
+    let (input, c) = component(CHROMA, legacy_separator, double)(input)?;
+    let (input, h) = component(HUE, legacy_separator, hue_angle)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_lch(l, c, h, alpha);
     Ok((input, c))
@@ -150,15 +188,12 @@ fn parse_css_lch65(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag_no_case("lch65("), tag_no_case("lch-d65(")))(input)?;
     let (input, _) = space0(input)?;
     // Percent reference range for L: 0% = 0, 100% = 100
-    let (input, l) = number_or_percentage(input, 100.0)?;
-    let (input, _) = space1(input)?;
+    let (input, l) = context(CSS_LIGHTNESS, |i| number_or_percentage(i, 100.0))(input)?;
     // Percent reference range for C: 0% = 0, 100% = 150
-    let (input, c) = number_or_percentage(input, 150.0)?;
-    let (input, _) = space1(input)?;
-    let (input, h) = hue_angle(input)?;
+    let (input, c) = component(CSS_CHROMA, space1, |i| number_or_percentage(i, 150.0))(input)?;
+    let (input, h) = component(HUE, space1, hue_angle)(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_lch(l, c, h, alpha);
     Ok((input, c))
@@ -173,12 +208,10 @@ fn parse_css_lch65(input: &str) -> IResult<&str, Color> {
 fn parse_lch_d65_color_space(input: &str) -> IResult<&str, Color> {
     fn lch_components(input: &str) -> IResult<&str, Color> {
         // Don't allow percentages because 100% = 1.0 for color space components.
-        let (input, l) = double(input)?;
-        let (input, _) = space1(input)?;
-        let (input, c) = double(input)?;
-        let (input, _) = space1(input)?;
+        let (input, l) = component(LIGHTNESS, space1, double)(input)?;
+        let (input, c) = component(CHROMA, space1, double)(input)?;
         // Optional angle units are allowed because they are not ambiguous.
-        let (input, h) = hue_angle(input)?;
+        let (input, h) = component(HUE, space1, hue_angle)(input)?;
 
         let c = Color::from_lch(l, c, h, 1.0);
         Ok((input, c))
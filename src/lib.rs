@@ -1,10 +1,14 @@
 pub mod ansi;
+pub mod blend;
 mod cmyk;
 mod color_scale;
+pub mod colormaps;
 pub mod colorspace;
 pub mod convert;
+mod css;
 pub mod delta_e;
 pub mod distinct;
+mod easing;
 mod helper;
 mod hsl;
 mod hsv;
@@ -14,9 +18,11 @@ mod lch;
 mod lms;
 pub mod matrix;
 pub mod named;
+mod oklab;
 pub mod parser;
 pub mod random;
 mod rgb;
+mod srgb_linear;
 mod types;
 mod xyz;
 
@@ -32,15 +38,18 @@ pub use hwb::HWBA;
 pub use lab::Lab;
 pub use lch::LCh;
 pub use lms::LMS;
+pub use oklab::{OkLCh, OkLab};
 pub use rgb::RGBA;
+pub use srgb_linear::LinearRGBA;
 pub use xyz::XYZ;
 
 use colorspace::ColorSpace;
-pub use helper::Fraction;
 use helper::MaxPrecision;
+pub use helper::{Fraction, HueInterpolation};
 use types::{Hue, Scalar};
 
-pub use color_scale::ColorScale;
+pub use color_scale::{ColorScale, Spline};
+pub use easing::Easing;
 
 /// The representation of a color.
 ///
@@ -128,6 +137,14 @@ impl Color {
         Self::from(&RGBA::new(r, g, b))
     }
 
+    /// Create a `Color` from an integer in ARGB representation (`0xAARRGGBB`), as used for
+    /// color integers on Android.
+    #[inline]
+    pub fn from_argb_u32(argb: u32) -> Color {
+        let [a, r, g, b] = argb.to_be_bytes();
+        Self::from_rgba(r, g, b, Scalar::from(a) / 255.0)
+    }
+
     /// Create a `Color` from RGB and alpha values between 0.0 and 1.0. Values outside this range
     /// will be clamped.
     #[inline]
@@ -274,6 +291,20 @@ impl Color {
         RGBA::<u8>::from(self).to_hex_string(leading_hash)
     }
 
+    /// Format the color as a RGBA-representation string with 8 hex digits (`#fc0070ff`). The
+    /// alpha channel is always included.
+    #[inline]
+    pub fn to_rgb_hex8_string(&self, leading_hash: bool) -> String {
+        RGBA::<u8>::from(self).to_hex8_string(leading_hash)
+    }
+
+    /// Format the color as a short RGB-representation string (`#f07` or `#f078`) if this is
+    /// possible without loss of precision, or as a 6/8 digit hex string otherwise.
+    #[inline]
+    pub fn to_rgb_hex_short_string(&self, leading_hash: bool) -> String {
+        RGBA::<u8>::from(self).to_hex_short_string(leading_hash)
+    }
+
     /// Convert a `Color` to its red, green, blue and alpha values. All numbers are from the range
     /// between 0.0 and 1.0.
     #[inline]
@@ -287,6 +318,18 @@ impl Color {
         self.to_rgba().to_u32()
     }
 
+    /// Return the color as an integer in ARGB representation (`0xAARRGGBB`)
+    #[inline]
+    pub fn to_argb_u32(&self) -> u32 {
+        self.to_rgba().to_argb_u32()
+    }
+
+    /// Return the color as an integer in RGBA representation (`0xRRGGBBAA`)
+    #[inline]
+    pub fn to_rgba_u32(&self) -> u32 {
+        self.to_rgba().to_rgba_u32()
+    }
+
     /// Get XYZ coordinates according to the CIE 1931 color space.
     ///
     /// See:
@@ -609,6 +652,34 @@ impl Color {
             .into_color()
     }
 
+    /// Like `mix`, but use the given method to interpolate the hue (in color spaces with a hue
+    /// component). For example, `HueInterpolation::Longer` takes the long way around the
+    /// hue circle.
+    pub fn mix_with_hue<C: ColorSpace>(
+        self: &Color,
+        other: &Color,
+        fraction: Fraction,
+        hue: HueInterpolation,
+    ) -> Color {
+        C::from_color(self)
+            .mix_with_hue(&C::from_color(other), fraction, hue)
+            .into_color()
+    }
+
+    /// Like `mix_with_hue`, but interpolate with premultiplied alpha (as in CSS). Transparent
+    /// colors contribute less to the color of the result. For example, mixing a fully
+    /// transparent color with red results in a semi-transparent red (instead of a grayish red).
+    pub fn mix_premultiplied<C: ColorSpace>(
+        self: &Color,
+        other: &Color,
+        fraction: Fraction,
+        hue: HueInterpolation,
+    ) -> Color {
+        C::from_color(self)
+            .mix_premultiplied(&C::from_color(other), fraction, hue)
+            .into_color()
+    }
+
     /// Alpha composite two colors, placing the second over the first.
     pub fn composite(&self, source: &Color) -> Color {
         let backdrop = self.to_rgba();
@@ -891,6 +962,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mix_premultiplied() {
+        let transparent = Color::from_rgba(0, 0, 0, 0.0);
+        let half = Fraction::from(0.5);
+        let hue = HueInterpolation::Shorter;
+        let expected = Color::from_rgba(255, 0, 0, 0.5);
+
+        assert_eq!(
+            expected,
+            transparent.mix_premultiplied::<RGBA<f64>>(&Color::red(), half, hue)
+        );
+        assert_eq!(
+            expected,
+            transparent.mix_premultiplied::<HSLA>(&Color::red(), half, hue)
+        );
+        assert_eq!(
+            expected,
+            transparent.mix_premultiplied::<HWBA>(&Color::red(), half, hue)
+        );
+        assert_eq!(
+            expected.to_rgb_hex_string(true),
+            transparent
+                .mix_premultiplied::<Lab>(&Color::red(), half, hue)
+                .to_rgb_hex_string(true)
+        );
+        assert_eq!(
+            expected.to_rgb_hex_string(true),
+            transparent
+                .mix_premultiplied::<LCh>(&Color::red(), half, hue)
+                .to_rgb_hex_string(true)
+        );
+
+        // without alpha, premultiplied mixing is the same as normal mixing
+        assert_eq!(
+            Color::red().mix::<Lab>(&Color::blue(), half),
+            Color::red().mix_premultiplied::<Lab>(&Color::blue(), half, hue)
+        );
+    }
+
     #[test]
     fn mixing_with_gray_preserves_hue() {
         let hue = 123.0;
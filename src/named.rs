@@ -1,7 +1,12 @@
-use nom::{character::complete::alpha1, combinator::all_consuming};
-use nom::{error::ErrorKind, Err, IResult};
+use nom::{
+    character::complete::alpha1,
+    combinator::all_consuming,
+    error::{ErrorKind, ParseError},
+    Err,
+};
 use once_cell::sync::Lazy;
 
+use crate::parser::IResult;
 use crate::Color;
 
 #[derive(Debug, Clone)]
@@ -171,17 +176,18 @@ pub static NAMED_COLORS: Lazy<[NamedColor; 148]> = Lazy::new(|| {
 });
 
 pub(crate) fn parse_named_color(input: &str) -> IResult<&str, Color> {
-    let (input, color) = all_consuming(alpha1)(input.trim())?;
+    let input = input.trim();
+    let (rest, color) = all_consuming(alpha1)(input)?;
     let nc = NAMED_COLORS
         .iter()
         .find(|nc| color.to_lowercase() == nc.name);
 
     match nc {
-        None => Err(Err::Error(nom::error::Error::new(
-            "Couldn't find matching named color",
+        None => Err(Err::Error(ParseError::from_error_kind(
+            input,
             ErrorKind::Alpha,
         ))),
-        Some(nc) => Ok((input, nc.color.clone())),
+        Some(nc) => Ok((rest, nc.color.clone())),
     }
 }
 
@@ -0,0 +1,353 @@
+use std::fmt;
+
+use nom::{
+    bytes::complete::tag_no_case,
+    character::complete::{space0, space1},
+    combinator::all_consuming,
+    error::context,
+};
+
+use crate::{
+    colorspace::ColorSpace,
+    convert::{gam_srgb, lin_srgb},
+    helper::{
+        interpolate, interpolate_angle, interpolate_premultiplied, mod_positive, HueInterpolation,
+    },
+    matrix::mat3_dot,
+    parser::{
+        closing_parenthesis, component, hue_angle, modern_alpha, number_or_percentage, IResult,
+    },
+    types::{Mat3, Scalar},
+    Color, Fraction, RGBA,
+};
+
+/// The Oklab color space, a perceptual color space that is better at predicting hue and
+/// lightness than CIELAB. The lightness `l` is in the range [0, 1].
+///
+/// See: <https://bottosson.github.io/posts/oklab/>
+#[derive(Debug, Clone, PartialEq)]
+pub struct OkLab {
+    pub l: Scalar,
+    pub a: Scalar,
+    pub b: Scalar,
+    pub alpha: Scalar,
+}
+
+/// The polar form of [`OkLab`] with chroma `c` and hue `h` (in degrees).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OkLCh {
+    pub l: Scalar,
+    pub c: Scalar,
+    pub h: Scalar,
+    pub alpha: Scalar,
+}
+
+/// Linear sRGB to LMS cone responses
+const M1: Mat3 = [
+    0.4122214708,
+    0.5363325363,
+    0.0514459929,
+    0.2119034982,
+    0.6806995451,
+    0.1073969566,
+    0.0883024619,
+    0.2817188376,
+    0.6299787005,
+];
+
+/// Non-linear LMS to Oklab
+const M2: Mat3 = [
+    0.2104542553,
+    0.7936177850,
+    -0.0040720468,
+    1.9779984951,
+    -2.4285922050,
+    0.4505937099,
+    0.0259040371,
+    0.7827717662,
+    -0.8086757660,
+];
+
+/// Oklab to non-linear LMS
+const M2_INV: Mat3 = [
+    1.0,
+    0.3963377774,
+    0.2158037573,
+    1.0,
+    -0.1055613458,
+    -0.0638541728,
+    1.0,
+    -0.0894841775,
+    -1.2914855480,
+];
+
+/// LMS cone responses to linear sRGB
+const M1_INV: Mat3 = [
+    4.0767416621,
+    -3.3077115913,
+    0.2309699292,
+    -1.2684380046,
+    2.6097574011,
+    -0.3413193965,
+    -0.0041960863,
+    -0.7034186147,
+    1.7076147010,
+];
+
+impl OkLab {
+    #[inline]
+    pub fn new(l: Scalar, a: Scalar, b: Scalar) -> Self {
+        Self::with_alpha(l, a, b, 1.0)
+    }
+
+    #[inline]
+    pub fn with_alpha(l: Scalar, a: Scalar, b: Scalar, alpha: Scalar) -> Self {
+        OkLab { l, a, b, alpha }
+    }
+}
+
+impl OkLCh {
+    #[inline]
+    pub fn new(l: Scalar, c: Scalar, h: Scalar) -> Self {
+        Self::with_alpha(l, c, h, 1.0)
+    }
+
+    #[inline]
+    pub fn with_alpha(l: Scalar, c: Scalar, h: Scalar, alpha: Scalar) -> Self {
+        OkLCh { l, c, h, alpha }
+    }
+}
+
+impl ColorSpace for OkLab {
+    fn from_color(c: &Color) -> Self {
+        Self::from(c)
+    }
+
+    fn into_color(self) -> Color {
+        Color::from(&self)
+    }
+
+    fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        Self {
+            l: interpolate(self.l, other.l, fraction),
+            a: interpolate(self.a, other.a, fraction),
+            b: interpolate(self.b, other.b, fraction),
+            alpha: interpolate(self.alpha, other.alpha, fraction),
+        }
+    }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, _hue: HueInterpolation) -> Self {
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            l: interpolate_premultiplied(self.l, alpha_a, other.l, alpha_b, fraction),
+            a: interpolate_premultiplied(self.a, alpha_a, other.a, alpha_b, fraction),
+            b: interpolate_premultiplied(self.b, alpha_a, other.b, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
+}
+
+impl ColorSpace for OkLCh {
+    fn from_color(c: &Color) -> Self {
+        Self::from(c)
+    }
+
+    fn into_color(self) -> Color {
+        Color::from(&self)
+    }
+
+    fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        self.mix_with_hue(other, fraction, HueInterpolation::Shorter)
+    }
+
+    fn mix_with_hue(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // make sure that the hue is preserved when mixing with gray colors
+        let self_hue = if self.c < 1e-4 { other.h } else { self.h };
+        let other_hue = if other.c < 1e-4 { self.h } else { other.h };
+
+        Self {
+            l: interpolate(self.l, other.l, fraction),
+            c: interpolate(self.c, other.c, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            alpha: interpolate(self.alpha, other.alpha, fraction),
+        }
+    }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, hue: HueInterpolation) -> Self {
+        // the hue of gray and fully transparent colors is ignored
+        let self_hue = if self.c < 1e-4 || self.alpha == 0.0 {
+            other.h
+        } else {
+            self.h
+        };
+        let other_hue = if other.c < 1e-4 || other.alpha == 0.0 {
+            self.h
+        } else {
+            other.h
+        };
+
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            l: interpolate_premultiplied(self.l, alpha_a, other.l, alpha_b, fraction),
+            c: interpolate_premultiplied(self.c, alpha_a, other.c, alpha_b, fraction),
+            h: interpolate_angle(self_hue, other_hue, fraction, hue),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
+}
+
+impl From<&Color> for OkLab {
+    fn from(color: &Color) -> Self {
+        let rgba = RGBA::<f64>::from(color);
+        let lms = mat3_dot(M1, lin_srgb([rgba.r, rgba.g, rgba.b]));
+        let [l, a, b] = mat3_dot(M2, lms.map(Scalar::cbrt));
+
+        OkLab::with_alpha(l, a, b, rgba.alpha)
+    }
+}
+
+impl From<&OkLab> for Color {
+    fn from(color: &OkLab) -> Self {
+        let lms = mat3_dot(M2_INV, [color.l, color.a, color.b]);
+        let [r, g, b] = gam_srgb(mat3_dot(M1_INV, lms.map(|v| v * v * v)));
+
+        Color::from_rgba_float(r, g, b, color.alpha)
+    }
+}
+
+impl From<&Color> for OkLCh {
+    fn from(color: &Color) -> Self {
+        Self::from(&OkLab::from(color))
+    }
+}
+
+impl From<&OkLab> for OkLCh {
+    fn from(lab: &OkLab) -> Self {
+        let &OkLab { l, a, b, alpha } = lab;
+
+        let c = Scalar::sqrt(a * a + b * b);
+        let h = mod_positive(Scalar::atan2(b, a).to_degrees(), 360.0);
+
+        OkLCh::with_alpha(l, c, h, alpha)
+    }
+}
+
+impl From<&OkLCh> for Color {
+    fn from(color: &OkLCh) -> Self {
+        let h = color.h.to_radians();
+        let a = color.c * Scalar::cos(h);
+        let b = color.c * Scalar::sin(h);
+
+        Self::from(&OkLab::with_alpha(color.l, a, b, color.alpha))
+    }
+}
+
+impl fmt::Display for OkLab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oklab({l} {a} {b})", l = self.l, a = self.a, b = self.b)
+    }
+}
+
+impl fmt::Display for OkLCh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oklch({l} {c} {h})", l = self.l, c = self.c, h = self.h)
+    }
+}
+
+const LIGHTNESS: &str = "expected lightness (a number or a percentage)";
+const A: &str = "expected a (a number or a percentage)";
+const B: &str = "expected b (a number or a percentage)";
+const CHROMA: &str = "expected chroma (a number or a percentage)";
+const HUE: &str = "expected hue (a number or an angle)";
+
+fn parse_css_oklab(input: &str) -> IResult<&str, Color> {
+    let (input, _) = tag_no_case("oklab(")(input)?;
+    let (input, _) = space0(input)?;
+    // Percent reference range for L: 0% = 0, 100% = 1
+    let (input, l) = context(LIGHTNESS, |i| number_or_percentage(i, 1.0))(input)?;
+    // Percent reference range for a and b: -100% = -0.4, 100% = 0.4
+    let (input, a) = component(A, space1, |i| number_or_percentage(i, 0.4))(input)?;
+    let (input, b) = component(B, space1, |i| number_or_percentage(i, 0.4))(input)?;
+    let (input, alpha) = modern_alpha(input)?;
+    let (input, _) = closing_parenthesis(input)?;
+
+    Ok((input, Color::from(&OkLab::with_alpha(l, a, b, alpha))))
+}
+
+fn parse_css_oklch(input: &str) -> IResult<&str, Color> {
+    let (input, _) = tag_no_case("oklch(")(input)?;
+    let (input, _) = space0(input)?;
+    // Percent reference range for L: 0% = 0, 100% = 1
+    let (input, l) = context(LIGHTNESS, |i| number_or_percentage(i, 1.0))(input)?;
+    // Percent reference range for C: 0% = 0, 100% = 0.4
+    let (input, c) = component(CHROMA, space1, |i| number_or_percentage(i, 0.4))(input)?;
+    let (input, h) = component(HUE, space1, hue_angle)(input)?;
+    let (input, alpha) = modern_alpha(input)?;
+    let (input, _) = closing_parenthesis(input)?;
+
+    Ok((input, Color::from(&OkLCh::with_alpha(l, c, h, alpha))))
+}
+
+pub(crate) fn parse_oklab_color(input: &str) -> IResult<&str, Color> {
+    all_consuming(parse_css_oklab)(input.trim())
+}
+
+pub(crate) fn parse_oklch_color(input: &str) -> IResult<&str, Color> {
+    all_consuming(parse_css_oklch)(input.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_color;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn conversion() {
+        // reference values from https://bottosson.github.io/posts/oklab/
+        let lab = OkLab::from(&Color::white());
+        assert_relative_eq!(1.0, lab.l, epsilon = 1e-4);
+        assert_relative_eq!(0.0, lab.a, epsilon = 1e-4);
+        assert_relative_eq!(0.0, lab.b, epsilon = 1e-4);
+
+        let lab = OkLab::from(&Color::red());
+        assert_relative_eq!(0.62796, lab.l, epsilon = 1e-4);
+        assert_relative_eq!(0.22486, lab.a, epsilon = 1e-4);
+        assert_relative_eq!(0.12585, lab.b, epsilon = 1e-4);
+
+        let lch = OkLCh::from(&Color::from_rgb(0, 0, 255));
+        assert_relative_eq!(0.45201, lch.l, epsilon = 1e-4);
+        assert_relative_eq!(0.31321, lch.c, epsilon = 1e-4);
+        assert_relative_eq!(264.052, lch.h, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn roundtrip() {
+        for color in [
+            Color::black(),
+            Color::white(),
+            Color::red(),
+            Color::from_rgba(12, 200, 87, 0.5),
+        ] {
+            assert_eq!(color, Color::from(&OkLab::from(&color)));
+            assert_eq!(color, Color::from(&OkLCh::from(&color)));
+        }
+    }
+
+    #[test]
+    fn parse_oklab_and_oklch() {
+        assert_eq!(
+            Some(Color::red()),
+            parse_color("oklab(0.62796 0.22486 0.12585)")
+        );
+        assert_eq!(
+            Some(Color::red()),
+            parse_color("oklch(62.796% 0.25768 29.234deg)")
+        );
+        assert_eq!(
+            Some(Color::from_rgba(0, 0, 255, 0.5)),
+            parse_color("OKLCH(0.45201 0.31321 264.052 / 50%)")
+        );
+        assert_eq!(None, parse_color("oklab(0.5, 0.1, 0.1)"));
+    }
+}
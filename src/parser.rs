@@ -1,19 +1,118 @@
 use nom::branch::alt;
 use nom::bytes::complete::{tag, tag_no_case};
-use nom::character::complete::{char, space0, space1};
-use nom::combinator::{all_consuming, opt, verify};
+use nom::character::complete::{char, satisfy, space0, space1};
+use nom::combinator::{all_consuming, not, opt, verify};
+use nom::error::{context, ContextError, ErrorKind};
 use nom::number::complete::double;
-use nom::sequence::{delimited, preceded};
-use nom::Parser;
-use nom::{error::Error, IResult};
+use nom::sequence::{delimited, preceded, terminated};
+use nom::{InputLength, Parser};
+
+use std::fmt;
 
 use crate::Color;
 use crate::{
-    cmyk::parse_cmyk_color, hsl::parse_hsl_color, hsv::parse_hsv_color, hwb::parse_hwb_color,
-    lab::parse_lab_color, lch::parse_lch_color, named::parse_named_color, rgb::parse_rgb_color,
+    ansi::parse_ansi_color,
+    cmyk::parse_cmyk_color,
+    css::{parse_color_mix, parse_relative_color},
+    hsl::parse_hsl_color,
+    hsv::parse_hsv_color,
+    hwb::parse_hwb_color,
+    lab::parse_lab_color,
+    lch::parse_lch_color,
+    named::parse_named_color,
+    named::NAMED_COLORS,
+    oklab::{parse_oklab_color, parse_oklch_color},
+    rgb::parse_rgb_color,
     xyz::parse_xyz_color,
 };
 
+/// The error type of the color parsers: the position at which parsing failed and, if known, a
+/// description of what was expected at that position. If several alternatives fail, the error
+/// of the alternative that got furthest is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SyntaxError<I> {
+    input: I,
+    expected: Option<&'static str>,
+    /// The name of the innermost color function that contains the error (`hsl` in `hsl(…)`)
+    function: Option<I>,
+}
+
+pub(crate) type IResult<I, O> = nom::IResult<I, O, SyntaxError<I>>;
+
+impl<I> SyntaxError<I> {
+    pub(crate) fn expected(input: I, expected: &'static str) -> Self {
+        SyntaxError {
+            input,
+            expected: Some(expected),
+            function: None,
+        }
+    }
+}
+
+impl<I: InputLength> nom::error::ParseError<I> for SyntaxError<I> {
+    fn from_error_kind(input: I, _kind: ErrorKind) -> Self {
+        SyntaxError {
+            input,
+            expected: None,
+            function: None,
+        }
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn or(self, other: Self) -> Self {
+        let (remaining, other_remaining) = (self.input.input_len(), other.input.input_len());
+        if other_remaining < remaining
+            || (other_remaining == remaining && self.expected.is_none() && other.expected.is_some())
+        {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl<I> ContextError<I> for SyntaxError<I> {
+    /// The innermost context describes the error best. It moves the position of the error to
+    /// the start of the part that could not be parsed (e.g. to the start of a component).
+    fn add_context(input: I, expected: &'static str, other: Self) -> Self {
+        match other.expected {
+            Some(_) => other,
+            None => SyntaxError {
+                function: other.function,
+                ..SyntaxError::expected(input, expected)
+            },
+        }
+    }
+}
+
+/// Fail at the given position, with a description of what was expected there.
+pub(crate) fn fail<I, O>(input: I, expected: &'static str) -> IResult<I, O> {
+    Err(nom::Err::Error(SyntaxError::expected(input, expected)))
+}
+
+/// A component of a color function, preceded by a separator. If the separator or the component
+/// is missing or invalid, the error describes the component, e.g. `expected saturation (a
+/// percentage)`.
+pub(crate) fn component<'a, O, S, SO, F>(
+    expected: &'static str,
+    separator: S,
+    value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    S: Parser<&'a str, SO, SyntaxError<&'a str>>,
+    F: Parser<&'a str, O, SyntaxError<&'a str>>,
+{
+    context(expected, preceded(separator, context(expected, value)))
+}
+
+/// The closing parenthesis of a color function, optionally preceded by whitespace.
+pub(crate) fn closing_parenthesis(input: &str) -> IResult<&str, char> {
+    preceded(space0, context("expected ')'", char(')')))(input)
+}
+
 fn comma_separator(input: &str) -> IResult<&str, &str> {
     let (input, _) = space0(input)?;
     let (input, _) = char(',')(input)?;
@@ -84,53 +183,127 @@ pub(crate) fn legacy_alpha<'a>(input: &'a str) -> IResult<&'a str, f64> {
     Ok((input, alpha.unwrap_or(1.0)))
 }
 
+/// An optional alpha value after a `/`. If there is a `/`, the alpha value is required.
 pub(crate) fn modern_alpha(input: &str) -> IResult<&str, f64> {
-    let (input, alpha) = opt(preceded(
-        delimited(space0, char('/'), space0),
-        alt((percentage, double)),
-    ))(input)?;
-    Ok((input, alpha.unwrap_or(1.0)))
+    let (rest, slash) = opt(delimited(space0, char('/'), space0))(input)?;
+    match slash {
+        Some(_) => context(
+            "expected an alpha value (a number or a percentage)",
+            alt((percentage, double)),
+        )(rest),
+        None => Ok((input, 1.0)),
+    }
 }
 
 fn parse_gray(input: &str) -> IResult<&str, Color> {
     let (input, _) = tag("gray(")(input)?;
     let (input, _) = space0(input)?;
-    let (input, g) = verify(alt((percentage, double)), |&d| d >= 0.)(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, g) = context(
+        "expected lightness (a non-negative number or percentage)",
+        verify(alt((percentage, double)), |&d| d >= 0.),
+    )(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_rgb_float(g, g, g);
 
     Ok((input, c))
 }
 
+/// Describes an unknown color space in `color(…)`. Errors with this description get a
+/// suggestion for the name of the color space.
+const COLOR_SPACE: &str = "expected a color space";
+
+/// The color spaces of the `color()` function, used for suggestions.
+const COLOR_SPACE_NAMES: &[&str] = &[
+    "srgb",
+    "srgb-linear",
+    "xyz",
+    "xyz-d65",
+    "hsv",
+    "lab-d65",
+    "lch-d65",
+];
+
+/// The names of the color functions, used for suggestions.
+const FUNCTION_NAMES: &[&str] = &[
+    "rgb",
+    "rgba",
+    "hsl",
+    "hsla",
+    "hsv",
+    "hsva",
+    "hwb",
+    "gray",
+    "lab",
+    "lch",
+    "lab-d65",
+    "lch-d65",
+    "oklab",
+    "oklch",
+    "cmyk",
+    "device-cmyk",
+    "ansi",
+    "ansi-8bit",
+    "color",
+    "color-mix",
+];
+
+/// A color in the `color()` function: `color(<color space> <components> [/ <alpha>])`. The
+/// `color` parser starts with the whitespace after the name of the color space.
 pub(crate) fn css_color_function<'a, O1, F, G>(
-    mut color_name: F,
+    color_name: F,
     mut color: G,
 ) -> impl FnMut(&'a str) -> IResult<&'a str, Color>
 where
-    F: Parser<&'a str, O1, Error<&'a str>>,
-    G: Parser<&'a str, Color, Error<&'a str>>,
+    F: Parser<&'a str, O1, SyntaxError<&'a str>>,
+    G: Parser<&'a str, Color, SyntaxError<&'a str>>,
 {
+    let mut color_name = context(
+        COLOR_SPACE,
+        terminated(color_name, not(satisfy(is_identifier_char))),
+    );
+
     move |input: &'a str| {
         let (input, _) = tag_no_case("color(")(input)?;
         let (input, _) = space0(input)?;
         let (input, _) = color_name.parse(input)?;
-        let (input, _) = space1(input)?;
 
         let (input, c) = color.parse(input)?;
         let (input, alpha) = modern_alpha(input)?;
 
-        let (input, _) = space0(input)?;
-        let (input, _) = char(')')(input)?;
+        let (input, _) = closing_parenthesis(input)?;
 
         let c = if alpha == 1.0 { c } else { c.with_alpha(alpha) };
         Ok((input, c))
     }
 }
 
-pub fn parse_color(input: &str) -> Option<Color> {
-    alt((
+/// Attach the name of the color function (`hsl` in `hsl(…)`) to errors inside of its
+/// parentheses, unless they already belong to a nested function.
+fn in_function<'a, F>(mut parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, Color>
+where
+    F: Parser<&'a str, Color, SyntaxError<&'a str>>,
+{
+    move |input: &'a str| {
+        parser.parse(input).map_err(|err| {
+            err.map(|mut err| {
+                let name = input
+                    .find('(')
+                    .filter(|&open| err.input.len() < input.len() - open)
+                    .map(|open| &input[..open])
+                    .filter(|name| !name.is_empty() && name.chars().all(is_identifier_char));
+                if err.function.is_none() {
+                    err.function = name;
+                }
+                err
+            })
+        })
+    }
+}
+
+/// Parse a color in any of the supported formats. Leading and trailing whitespace is ignored.
+pub(crate) fn color(input: &str) -> IResult<&str, Color> {
+    in_function(alt((
         parse_rgb_color,
         parse_hsl_color,
         parse_hsv_color,
@@ -140,10 +313,338 @@ pub fn parse_color(input: &str) -> Option<Color> {
         parse_lab_color,
         parse_lch_color,
         parse_cmyk_color,
+        parse_oklab_color,
+        parse_oklch_color,
+        parse_relative_color,
+        parse_color_mix,
+        parse_ansi_color,
         parse_named_color,
-    ))(input.trim())
-    .ok()
-    .map(|(_, c)| c)
+    )))(input.trim())
+}
+
+pub fn parse_color(input: &str) -> Option<Color> {
+    color(input).ok().map(|(_, c)| c)
+}
+
+/// Describes why a color could not be parsed, see [`try_parse_color`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Byte offset in the input at which the problem has been detected
+    pub offset: usize,
+    /// Description of the problem and the expected syntax, e.g. `hsl(): expected saturation (a
+    /// percentage)`
+    pub message: String,
+    /// A similar color name or function name, if the input looks like a misspelled one
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean '{}'?)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Like [`parse_color`], but describes the problem if the input can not be parsed.
+pub fn try_parse_color(input: &str) -> Result<Color, ParseError> {
+    match color(input) {
+        Ok((_, c)) => Ok(c),
+        Err(nom::Err::Error(err)) | Err(nom::Err::Failure(err)) => Err(describe(input, err)),
+        Err(nom::Err::Incomplete(_)) => Err(error(input.len(), "expected a color")),
+    }
+}
+
+/// Optimal string alignment distance: the number of insertions, deletions, substitutions and
+/// transpositions of adjacent characters that are needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+/// Find the candidate that is most similar to the given (misspelled) word.
+fn closest_match<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    let max_distance = std::cmp::max(1, word.chars().count() / 3);
+
+    candidates
+        .map(|candidate| (edit_distance(&word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn error(offset: usize, message: impl Into<String>) -> ParseError {
+    ParseError {
+        offset,
+        message: message.into(),
+        suggestion: None,
+    }
+}
+
+/// The identifier (color name, function name, …) that starts at the given position.
+fn identifier_at(input: &str, position: usize) -> &str {
+    let rest = &input[position..];
+    let end = rest
+        .find(|c: char| !is_identifier_char(c))
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Turn the error of the color parsers into a description of the problem.
+fn describe(input: &str, err: SyntaxError<&str>) -> ParseError {
+    let offset_of = |part: &str| {
+        (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .unwrap_or(0)
+    };
+    let start = input.len() - input.trim_start().len();
+    let trimmed = input.trim();
+    let prefix = err
+        .function
+        .map(|name| format!("{}(): ", name.to_lowercase()))
+        .unwrap_or_default();
+
+    match (err.expected, err.function) {
+        (Some(COLOR_SPACE), _) => {
+            let offset = offset_of(err.input);
+            let space = identifier_at(input, offset);
+            if space.is_empty() {
+                return error(offset, format!("{}{}", prefix, COLOR_SPACE));
+            }
+            ParseError {
+                suggestion: closest_match(space, COLOR_SPACE_NAMES.iter().copied()).map(Into::into),
+                ..error(offset, format!("{}unknown color space '{}'", prefix, space))
+            }
+        }
+        (Some(expected), _) => error(offset_of(err.input), format!("{}{}", prefix, expected)),
+        (None, Some(_)) => {
+            let rest = err.input.trim_start();
+            let offset = offset_of(rest);
+            if rest.is_empty() {
+                error(offset, format!("{}unexpected end of input", prefix))
+            } else if input[..offset].trim_end().ends_with(')') {
+                error(offset, "unexpected characters after ')'")
+            } else {
+                let c = rest.chars().next().unwrap_or_default();
+                error(offset, format!("{}unexpected '{}'", prefix, c))
+            }
+        }
+        (None, None) => {
+            // Nothing but the start of the input could be parsed. Check for misspelled
+            // identifiers.
+            let name = identifier_at(input, start);
+            if trimmed.is_empty() {
+                error(start, "expected a color")
+            } else if !name.is_empty() && trimmed[name.len()..].starts_with('(') {
+                ParseError {
+                    suggestion: closest_match(name, FUNCTION_NAMES.iter().copied())
+                        .filter(|s| !s.eq_ignore_ascii_case(name))
+                        .map(|s| format!("{}()", s)),
+                    ..error(start, format!("unknown color function '{}()'", name))
+                }
+            } else if trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+                let names = NAMED_COLORS.iter().map(|nc| nc.name);
+                ParseError {
+                    suggestion: closest_match(trimmed, names).map(Into::into),
+                    ..error(start, format!("unknown color name '{}'", trimmed))
+                }
+            } else {
+                error(
+                    start,
+                    "expected a color name, a hex code like '#ff0077' or a color function like \
+                     'rgb(255, 0, 119)'",
+                )
+            }
+        }
+    }
+}
+
+/// A color literal that has been found in a text by [`find_colors`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorMatch<'a> {
+    /// Byte offset of the start of the literal
+    pub start: usize,
+    /// Byte offset of the end of the literal (exclusive)
+    pub end: usize,
+    /// The color literal as it appears in the text (e.g. `#ff0077` or `rgb(255, 0, 119)`)
+    pub text: &'a str,
+    pub color: Color,
+}
+
+/// The longest color literal in functional notation that is considered by [`find_colors`].
+const MAX_FUNCTION_LENGTH: usize = 128;
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Whether a word starts at the given position. Identifiers that follow a `.` (as in `rgb.red`
+/// or the CSS class `.red`) are not considered to be separate words.
+fn is_word_start(text: &str, position: usize) -> bool {
+    !text[..position]
+        .chars()
+        .next_back()
+        .map_or(false, |c| is_identifier_char(c) || c == '.')
+}
+
+/// Whether a word ends at the given position. A `.` only ends a word if it is not followed by
+/// another identifier (as in `red.500`).
+fn is_word_end(text: &str, position: usize) -> bool {
+    let mut chars = text[position..].chars();
+    match chars.next() {
+        Some('.') => !chars.next().map_or(false, is_identifier_char),
+        Some(c) => !is_identifier_char(c),
+        None => true,
+    }
+}
+
+/// Whether the text before the position introduces a value, as in `color: #123`, `fill="#123"`
+/// or `"primary": "#123"`.
+fn is_value_context(text: &str, position: usize) -> bool {
+    matches!(
+        text[..position].trim_end().chars().next_back(),
+        Some(':' | '=' | '"' | '\'')
+    )
+}
+
+/// Whether the word that ends at the given position is part of a CSS selector, as in
+/// `#fade > a { … }` or `#add, .b { … }`: it is followed by a selector continuation and the text
+/// after it opens a rule block before a declaration ends.
+fn is_selector(text: &str, position: usize) -> bool {
+    let rest = text[position..].trim_start();
+    rest.starts_with(['{', ',', '>', '.', ':', '+', '~', '['])
+        && rest
+            .find(['{', ';', '}'])
+            .map_or(false, |i| rest[i..].starts_with('{'))
+}
+
+/// Find all color literals in a text: hex colors (`#f07`, `#ff0077`) and colors in functional
+/// notation (`rgb(…)`, `hsl(…)`, `lab(…)`, `color(…)`, …). Hex colors are only recognized as
+/// whole words. Hex colors that consist of digits only (`#123`) could be references to issues
+/// and are only recognized where a value is expected (after `:`, `=` or a quote). Outside of a
+/// value, words that look like hex colors are not recognized in CSS selectors (`#fade > a { … }`)
+/// or URL fragments (`/#add`).
+pub fn find_colors(text: &str) -> Vec<ColorMatch<'_>> {
+    find_color_literals(text, false)
+}
+
+/// Like [`find_colors`], but named colors (`red`) are recognized as well. Note that this also
+/// finds color names that are used as ordinary words or identifiers (`let red = …`).
+pub fn find_colors_with_names(text: &str) -> Vec<ColorMatch<'_>> {
+    find_color_literals(text, true)
+}
+
+fn find_color_literals(text: &str, names: bool) -> Vec<ColorMatch<'_>> {
+    let mut matches = vec![];
+
+    let mut position = 0;
+    while let Some(c) = text[position..].chars().next() {
+        let at_word_boundary = is_word_start(text, position);
+
+        let literal = |end: usize| parse_color(&text[position..end]).map(|color| (end, color));
+
+        let color = if !at_word_boundary {
+            None
+        } else if c == '#' {
+            let digits = text[position + 1..]
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .map_or(text.len(), |i| position + 1 + i);
+            let only_decimal_digits = text[position + 1..digits]
+                .chars()
+                .all(|c| c.is_ascii_digit());
+            let in_url = text[..position].ends_with('/');
+            if matches!(digits - position - 1, 3 | 4 | 6 | 8)
+                && is_word_end(text, digits)
+                && (is_value_context(text, position)
+                    || !(only_decimal_digits || in_url || is_selector(text, digits)))
+            {
+                literal(digits)
+            } else {
+                None
+            }
+        } else if c.is_ascii_alphabetic() {
+            let identifier = text[position..]
+                .find(|c: char| !is_identifier_char(c))
+                .map_or(text.len(), |i| position + i);
+
+            if text[identifier..].starts_with('(') {
+                let mut depth = 0;
+                text[identifier..]
+                    .char_indices()
+                    .take_while(|(i, _)| *i < MAX_FUNCTION_LENGTH)
+                    .find(|(_, c)| {
+                        match c {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        depth == 0
+                    })
+                    .and_then(|(i, _)| literal(identifier + i + 1))
+            } else if names && is_word_end(text, identifier) {
+                parse_named_color(&text[position..identifier])
+                    .ok()
+                    .map(|(_, color)| (identifier, color))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        match color {
+            Some((end, color)) => {
+                matches.push(ColorMatch {
+                    start: position,
+                    end,
+                    text: &text[position..end],
+                    color,
+                });
+                position = end;
+            }
+            None => {
+                // skip the rest of an identifier that is not a color
+                let skip = if c.is_ascii_alphanumeric() || c == '#' {
+                    text[position + 1..]
+                        .find(|c: char| !is_identifier_char(c))
+                        .map_or(text.len() - position, |i| i + 1)
+                } else {
+                    c.len_utf8()
+                };
+                position += skip;
+            }
+        }
+    }
+
+    matches
 }
 
 #[cfg(test)]
@@ -419,3 +920,138 @@ fn parse_device_cmyk_string() {
         parse_color("device-cmyk(0.8 0.2 0.6 0.4)")
     );
 }
+
+#[test]
+fn parse_errors() {
+    fn err(input: &str) -> (usize, String, Option<String>) {
+        let err = try_parse_color(input).unwrap_err();
+        (err.offset, err.message, err.suggestion)
+    }
+
+    assert_eq!(
+        (8, "hsl(): expected saturation (a percentage)".into(), None),
+        err("hsl(10, 50, 50%)")
+    );
+    assert_eq!(
+        (
+            0,
+            "unknown color name 'cornflowrblue'".into(),
+            Some("cornflowerblue".into())
+        ),
+        err("cornflowrblue")
+    );
+    assert_eq!(
+        (4, "expected a hex digit (0-9 or a-f)".into(), None),
+        err("  #fg0")
+    );
+    assert_eq!(
+        (6, "expected 3, 4, 6 or 8 hex digits".into(), None),
+        err("#12345")
+    );
+    assert_eq!(
+        (7, "rgb(): expected blue (a number)".into(), None),
+        err("rgb(1 2)")
+    );
+    assert_eq!(
+        (12, "hwb(): expected ')'".into(), None),
+        err("hwb(1 2% 3% 4%)")
+    );
+    assert_eq!(
+        (9, "rgb(): expected green (a percentage)".into(), None),
+        err("rgb(100% 0 0)")
+    );
+    assert_eq!(
+        (
+            12,
+            "rgb(): expected an alpha value (a number or a percentage)".into(),
+            None
+        ),
+        err("rgb(1 2 3 / x)")
+    );
+    assert_eq!((11, "rgb(): expected ')'".into(), None), err("rgb(1, 2, 3"));
+    assert_eq!(
+        (11, "unexpected characters after ')'".into(), None),
+        err("rgb(1 2 3) x")
+    );
+    assert_eq!(
+        (
+            0,
+            "unknown color function 'rbg()'".into(),
+            Some("rgb()".into())
+        ),
+        err("rbg(1, 2, 3)")
+    );
+    assert_eq!(
+        (
+            6,
+            "color(): unknown color space 'srbg'".into(),
+            Some("srgb".into())
+        ),
+        err("color(srbg 1 0 0)")
+    );
+    assert_eq!(
+        (9, "expected blue (one to four hex digits)".into(), None),
+        err("rgb:ff/80")
+    );
+    assert_eq!(
+        (
+            5,
+            "ansi(): expected a color code (an integer between 0 and 255)".into(),
+            None
+        ),
+        err("ansi(300)")
+    );
+    // errors in nested colors point into the nested color
+    assert_eq!(
+        (26, "rgb(): expected blue (a number)".into(), None),
+        err("color-mix(in srgb, rgb(1 2), red)")
+    );
+    assert_eq!(
+        (
+            18,
+            "color-mix(): percentages must not be negative and must not add up to zero".into(),
+            None
+        ),
+        err("color-mix(in lab, red 0%, blue 0%)")
+    );
+    assert_eq!((2, "expected a color".into(), None), err("  "));
+}
+
+#[test]
+fn find_colors_in_text() {
+    let text = ".button { color: #F07; background: rgba(0, 0, 255, 0.5) } /* red */";
+    assert_eq!(2, find_colors(text).len());
+
+    let matches = find_colors_with_names(text);
+
+    assert_eq!(3, matches.len());
+    assert_eq!("#F07", matches[0].text);
+    assert_eq!(rgb(255, 0, 119), matches[0].color);
+    assert_eq!("rgba(0, 0, 255, 0.5)", matches[1].text);
+    assert_eq!(rgba(0, 0, 255, 0.5), matches[1].color);
+    assert_eq!("red", matches[2].text);
+    assert_eq!(&text[matches[2].start..matches[2].end], "red");
+}
+
+#[test]
+fn find_colors_word_boundaries() {
+    assert!(find_colors_with_names("#ff00778 --red redux tan_x x#fff").is_empty());
+    assert!(find_colors_with_names("translate(10px) var(--red)").is_empty());
+    assert!(find_colors_with_names("rgb.red .red red_x theme.red.500").is_empty());
+    assert_eq!(1, find_colors("lab(50 40 20);").len());
+    assert_eq!(1, find_colors("ümlaut #abc").len());
+    assert_eq!(1, find_colors_with_names("The color is red.").len());
+
+    // digits-only hex colors are only found in value context
+    assert!(find_colors("Fixes #123 and (#1234)").is_empty());
+    assert_eq!(3, find_colors("a: #123; b=#123456 \"#1234\"").len());
+
+    // words that look like hex colors are not found in selectors and URL fragments
+    assert_eq!(1, find_colors("#add, #fade > a { color: #abc }").len());
+    assert_eq!(
+        1,
+        find_colors("#bad:hover,\n#cafe.x { color: #abc; }").len()
+    );
+    assert!(find_colors("https://example.com/#add").is_empty());
+    assert_eq!(3, find_colors("linear-gradient(#abc, #def); #fade.").len());
+}
@@ -3,21 +3,24 @@ use std::fmt;
 use nom::{
     branch::alt,
     bytes::complete::{tag, tag_no_case},
-    character::complete::{char, hex_digit1, space0, space1},
-    combinator::{all_consuming, cond, opt},
-    error::ErrorKind,
+    character::complete::{char, digit1, hex_digit1, space0, space1},
+    combinator::{all_consuming, cond, opt, recognize},
+    error::{context, ErrorKind, ParseError},
     number::complete::double,
-    Err, IResult,
+    sequence::pair,
+    Err,
 };
 
 use crate::{
     colorspace::ColorSpace,
     convert::gam_srgb,
-    helper::{clamp, interpolate, mod_positive, MaxPrecision},
+    helper::{
+        clamp, interpolate, interpolate_premultiplied, mod_positive, HueInterpolation, MaxPrecision,
+    },
     hsl::HSLA,
     parser::{
-        css_color_function, legacy_alpha, legacy_separator, modern_alpha, number_or_percentage,
-        percentage,
+        closing_parenthesis, component, css_color_function, fail, legacy_alpha, legacy_separator,
+        modern_alpha, number_or_percentage, percentage, IResult,
     },
     types::Scalar,
     Color, Format, Fraction,
@@ -48,6 +51,16 @@ impl ColorSpace for RGBA<f64> {
             alpha: interpolate(self.alpha, other.alpha, fraction),
         }
     }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, _hue: HueInterpolation) -> Self {
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            r: interpolate_premultiplied(self.r, alpha_a, other.r, alpha_b, fraction),
+            g: interpolate_premultiplied(self.g, alpha_a, other.g, alpha_b, fraction),
+            b: interpolate_premultiplied(self.b, alpha_a, other.b, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
 }
 
 impl From<&Color> for RGBA<f64> {
@@ -176,6 +189,22 @@ impl RGBA<u8> {
         u32::from(self.r).wrapping_shl(16) + u32::from(self.g).wrapping_shl(8) + u32::from(self.b)
     }
 
+    /// Return the color as an integer in ARGB representation (`0xAARRGGBB`), as used on Android
+    #[inline]
+    pub fn to_argb_u32(&self) -> u32 {
+        u32::from(self.alpha_u8()).wrapping_shl(24) + self.to_u32()
+    }
+
+    /// Return the color as an integer in RGBA representation (`0xRRGGBBAA`)
+    #[inline]
+    pub fn to_rgba_u32(&self) -> u32 {
+        self.to_u32().wrapping_shl(8) + u32::from(self.alpha_u8())
+    }
+
+    fn alpha_u8(&self) -> u8 {
+        (self.alpha * 255.).round() as u8
+    }
+
     /// Format the color as a RGB-representation string (`rgba(255, 127, 0, 0.5)`). If the alpha
     /// channel is `1.0`, the simplified `rgb()` format will be used instead.
     pub fn to_color_string(&self, format: Format) -> String {
@@ -215,7 +244,43 @@ impl RGBA<u8> {
             if self.alpha == 1.0 {
                 "".to_string()
             } else {
-                format!("{:02x}", (self.alpha * 255.).round() as u8)
+                format!("{:02x}", self.alpha_u8())
+            }
+        )
+    }
+
+    /// Format the color as a RGBA-representation string with 8 hex digits (`#fc0070ff`),
+    /// including the alpha channel even if it is `1.0`.
+    pub fn to_hex8_string(&self, leading_hash: bool) -> String {
+        format!(
+            "{}{:08x}",
+            if leading_hash { "#" } else { "" },
+            self.to_rgba_u32()
+        )
+    }
+
+    /// Format the color as a short RGB-representation string with 3 hex digits (`#f07`), or 4
+    /// digits if the alpha channel is not `1.0`. If the color can not be represented exactly in
+    /// the short notation, this falls back to the 6 or 8 digit output of `to_hex_string`.
+    pub fn to_hex_short_string(&self, leading_hash: bool) -> String {
+        let alpha = self.alpha_u8();
+        // a byte can be written as a single hex digit if both digits are equal (0x11 * n)
+        let is_short = |byte: u8| byte % 0x11 == 0;
+
+        if !(is_short(self.r) && is_short(self.g) && is_short(self.b) && is_short(alpha)) {
+            return self.to_hex_string(leading_hash);
+        }
+
+        format!(
+            "{}{:x}{:x}{:x}{}",
+            if leading_hash { "#" } else { "" },
+            self.r / 0x11,
+            self.g / 0x11,
+            self.b / 0x11,
+            if self.alpha == 1.0 {
+                "".to_string()
+            } else {
+                format!("{:x}", alpha / 0x11)
             }
         )
     }
@@ -250,6 +315,18 @@ impl RGBA<f64> {
     }
 }
 
+const RED: &str = "expected red (a number)";
+const GREEN: &str = "expected green (a number)";
+const BLUE: &str = "expected blue (a number)";
+const RED_PERCENTAGE: &str = "expected red (a percentage)";
+const GREEN_PERCENTAGE: &str = "expected green (a percentage)";
+const BLUE_PERCENTAGE: &str = "expected blue (a percentage)";
+const RED_COMPONENT: &str = "expected red (a number or a percentage)";
+const GREEN_COMPONENT: &str = "expected green (a number or a percentage)";
+const BLUE_COMPONENT: &str = "expected blue (a number or a percentage)";
+const HEX_DIGITS: &str = "expected 3, 4, 6 or 8 hex digits";
+const HEX_DIGIT: &str = "expected a hex digit (0-9 or a-f)";
+
 pub(crate) fn parse_rgb_color(input: &str) -> IResult<&str, Color> {
     alt((
         all_consuming(parse_rgb_hex),
@@ -259,6 +336,11 @@ pub(crate) fn parse_rgb_color(input: &str) -> IResult<&str, Color> {
         all_consuming(parse_percentage_rgb),
         all_consuming(parse_srgb_color_space),
         all_consuming(parse_srgb_linear_color_space),
+        all_consuming(parse_hex_integer),
+        all_consuming(parse_signed_argb_integer),
+        all_consuming(parse_x11_rgb),
+        all_consuming(parse_x11_rgbi),
+        all_consuming(parse_float_tuple),
     ))(input.trim())
 }
 
@@ -267,8 +349,15 @@ fn parse_rgb_hex(input: &str) -> IResult<&str, Color> {
         u8::from_str_radix(num, 16).unwrap()
     }
 
-    let (input, _) = opt(char('#'))(input)?;
-    let (input, hex_chars) = hex_digit1(input)?;
+    let (input, hash) = opt(char('#'))(input)?;
+    let (rest, hex_chars) = match hash {
+        Some(_) => context(HEX_DIGITS, hex_digit1)(input)?,
+        None => hex_digit1(input)?,
+    };
+    if hash.is_some() && rest.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return fail(rest, HEX_DIGIT);
+    }
+    let input = rest;
     match hex_chars.len() {
         // RRGGBB
         6 => {
@@ -307,25 +396,122 @@ fn parse_rgb_hex(input: &str) -> IResult<&str, Color> {
             let a = (a * 16 + a) as f64 / 255.0;
             Ok((input, Color::from_rgba(r, g, b, a)))
         }
-        _ => Err(Err::Error(nom::error::Error::new(
-            "Expected hex string of 3 or 6 characters length",
+        // Without a '#', only a word that consists of hex digits is treated as a hex color
+        _ if hash.is_some() || input.is_empty() => fail(input, HEX_DIGITS),
+        _ => Err(Err::Error(ParseError::from_error_kind(
+            input,
             ErrorKind::Many1,
         ))),
     }
 }
 
+/// `0xRRGGBB` or `0xAARRGGBB` (alpha first, like color integers in Android or Qt)
+fn parse_hex_integer(input: &str) -> IResult<&str, Color> {
+    const HEX_INTEGER_DIGITS: &str = "expected 6 or 8 hex digits";
+
+    let (input, _) = tag_no_case("0x")(input)?;
+    let (input, hex_chars) = context(HEX_INTEGER_DIGITS, hex_digit1)(input)?;
+    let argb = match hex_chars.len() {
+        6 => 0xff00_0000 | u32::from_str_radix(hex_chars, 16).unwrap(),
+        8 => u32::from_str_radix(hex_chars, 16).unwrap(),
+        _ => return fail(input, HEX_INTEGER_DIGITS),
+    };
+    Ok((input, Color::from_argb_u32(argb)))
+}
+
+/// Android color integers (`-65536` for red). Opaque colors are negative because the alpha
+/// channel is stored in the most significant byte of a signed 32-bit integer. Positive integers
+/// are not accepted because they can not be distinguished from hex colors without `#`.
+fn parse_signed_argb_integer(input: &str) -> IResult<&str, Color> {
+    let (rest, digits) = recognize(pair(char('-'), digit1))(input)?;
+    match digits.parse::<i32>() {
+        Ok(argb) => Ok((rest, Color::from_argb_u32(argb as u32))),
+        Err(_) => fail(input, "expected a signed 32-bit color integer"),
+    }
+}
+
+/// X11 color specification `rgb:<r>/<g>/<b>` with one to four hex digits per channel, as used
+/// by `xrdb` and in terminal responses to color queries.
+fn parse_x11_rgb(input: &str) -> IResult<&str, Color> {
+    fn channel(input: &str) -> IResult<&str, Scalar> {
+        let (rest, hex_chars) = hex_digit1(input)?;
+        if hex_chars.len() > 4 {
+            return Err(Err::Error(ParseError::from_error_kind(
+                input,
+                ErrorKind::Many1,
+            )));
+        }
+        let max = (1u32 << (4 * hex_chars.len())) - 1;
+        let value = u32::from_str_radix(hex_chars, 16).unwrap();
+        Ok((rest, Scalar::from(value) / Scalar::from(max)))
+    }
+
+    let (input, _) = tag_no_case("rgb:")(input)?;
+    let (input, r) = context("expected red (one to four hex digits)", channel)(input)?;
+    let (input, g) = component(
+        "expected green (one to four hex digits)",
+        char('/'),
+        channel,
+    )(input)?;
+    let (input, b) =
+        component("expected blue (one to four hex digits)", char('/'), channel)(input)?;
+
+    Ok((input, Color::from_rgb_float(r, g, b)))
+}
+
+/// X11 color specification `rgbi:<r>/<g>/<b>` with intensities between 0 and 1
+fn parse_x11_rgbi(input: &str) -> IResult<&str, Color> {
+    let (input, _) = tag_no_case("rgbi:")(input)?;
+    let (input, r) = context("expected red (a number between 0 and 1)", double)(input)?;
+    let (input, g) = component(
+        "expected green (a number between 0 and 1)",
+        char('/'),
+        double,
+    )(input)?;
+    let (input, b) = component(
+        "expected blue (a number between 0 and 1)",
+        char('/'),
+        double,
+    )(input)?;
+
+    Ok((input, Color::from_rgb_float(r, g, b)))
+}
+
+/// Tuples of floating point values between 0 and 1, like `(0.2, 0.4, 0.6)` or
+/// `(0.2, 0.4, 0.6, 0.5)`
+fn parse_float_tuple(input: &str) -> IResult<&str, Color> {
+    let (input, _) = char('(')(input)?;
+    let (input, _) = space0(input)?;
+    let (input, r) = context("expected red (a number between 0 and 1)", double)(input)?;
+    let (input, g) = component(
+        "expected green (a number between 0 and 1)",
+        legacy_separator,
+        double,
+    )(input)?;
+    let (input, b) = component(
+        "expected blue (a number between 0 and 1)",
+        legacy_separator,
+        double,
+    )(input)?;
+    let (input, alpha) = legacy_alpha(input)?;
+    let (input, _) = closing_parenthesis(input)?;
+
+    Ok((input, Color::from_rgba_float(r, g, b, alpha)))
+}
+
 fn parse_numeric_rgb(input: &str) -> IResult<&str, Color> {
     let (input, prefixed) = opt(alt((tag("rgb("), tag("rgba("))))(input)?;
     let is_prefixed = prefixed.is_some();
     let (input, _) = space0(input)?;
-    let (input, r) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, g) = double(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, b) = double(input)?;
+    let (input, r) = if is_prefixed {
+        context(RED, double)(input)?
+    } else {
+        double(input)?
+    };
+    let (input, g) = component(GREEN, legacy_separator, double)(input)?;
+    let (input, b) = component(BLUE, legacy_separator, double)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = cond(is_prefixed, char(')'))(input)?;
+    let (input, _) = cond(is_prefixed, closing_parenthesis)(input)?;
 
     let r = r / 255.;
     let g = g / 255.;
@@ -338,14 +524,11 @@ fn parse_numeric_rgb(input: &str) -> IResult<&str, Color> {
 fn parse_css_numeric_rgb(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag_no_case("rgb("), tag_no_case("rgba(")))(input)?;
     let (input, _) = space0(input)?;
-    let (input, r) = double(input)?;
-    let (input, _) = space1(input)?;
-    let (input, g) = double(input)?;
-    let (input, _) = space1(input)?;
-    let (input, b) = double(input)?;
+    let (input, r) = context(RED, double)(input)?;
+    let (input, g) = component(GREEN, space1, double)(input)?;
+    let (input, b) = component(BLUE, space1, double)(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let r = r / 255.;
     let g = g / 255.;
@@ -359,14 +542,15 @@ fn parse_percentage_rgb(input: &str) -> IResult<&str, Color> {
     let (input, prefixed) = opt(alt((tag("rgb("), tag("rgba("))))(input)?;
     let is_prefixed = prefixed.is_some();
     let (input, _) = space0(input)?;
-    let (input, r) = percentage(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, g) = percentage(input)?;
-    let (input, _) = legacy_separator(input)?;
-    let (input, b) = percentage(input)?;
+    let (input, r) = if is_prefixed {
+        context(RED_PERCENTAGE, percentage)(input)?
+    } else {
+        percentage(input)?
+    };
+    let (input, g) = component(GREEN_PERCENTAGE, legacy_separator, percentage)(input)?;
+    let (input, b) = component(BLUE_PERCENTAGE, legacy_separator, percentage)(input)?;
     let (input, alpha) = legacy_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = cond(is_prefixed, char(')'))(input)?;
+    let (input, _) = cond(is_prefixed, closing_parenthesis)(input)?;
 
     let c = Color::from_rgba_float(r, g, b, alpha);
     Ok((input, c))
@@ -375,14 +559,11 @@ fn parse_percentage_rgb(input: &str) -> IResult<&str, Color> {
 fn parse_css_percentage_rgb(input: &str) -> IResult<&str, Color> {
     let (input, _) = alt((tag_no_case("rgb("), tag_no_case("rgba(")))(input)?;
     let (input, _) = space0(input)?;
-    let (input, r) = percentage(input)?;
-    let (input, _) = space1(input)?;
-    let (input, g) = percentage(input)?;
-    let (input, _) = space1(input)?;
-    let (input, b) = percentage(input)?;
+    let (input, r) = context(RED_PERCENTAGE, percentage)(input)?;
+    let (input, g) = component(GREEN_PERCENTAGE, space1, percentage)(input)?;
+    let (input, b) = component(BLUE_PERCENTAGE, space1, percentage)(input)?;
     let (input, alpha) = modern_alpha(input)?;
-    let (input, _) = space0(input)?;
-    let (input, _) = char(')')(input)?;
+    let (input, _) = closing_parenthesis(input)?;
 
     let c = Color::from_rgba_float(r, g, b, alpha);
     Ok((input, c))
@@ -390,11 +571,10 @@ fn parse_css_percentage_rgb(input: &str) -> IResult<&str, Color> {
 
 fn parse_srgb_color_space(input: &str) -> IResult<&str, Color> {
     fn srgb_components(input: &str) -> IResult<&str, Color> {
-        let (input, r) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, g) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, b) = number_or_percentage(input, 1.0)?;
+        let value = |input| number_or_percentage(input, 1.0);
+        let (input, r) = component(RED_COMPONENT, space1, value)(input)?;
+        let (input, g) = component(GREEN_COMPONENT, space1, value)(input)?;
+        let (input, b) = component(BLUE_COMPONENT, space1, value)(input)?;
 
         let c = Color::from_rgb_float(r, g, b);
         Ok((input, c))
@@ -405,11 +585,10 @@ fn parse_srgb_color_space(input: &str) -> IResult<&str, Color> {
 
 fn parse_srgb_linear_color_space(input: &str) -> IResult<&str, Color> {
     fn lin_srgb_components(input: &str) -> IResult<&str, Color> {
-        let (input, r_) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, g_) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, b_) = number_or_percentage(input, 1.0)?;
+        let value = |input| number_or_percentage(input, 1.0);
+        let (input, r_) = component(RED_COMPONENT, space1, value)(input)?;
+        let (input, g_) = component(GREEN_COMPONENT, space1, value)(input)?;
+        let (input, b_) = component(BLUE_COMPONENT, space1, value)(input)?;
 
         let [r, g, b] = gam_srgb([r_, g_, b_]);
         let c = Color::from_rgb_float(r, g, b);
@@ -523,6 +702,40 @@ mod tests {
         let c = RGBA::new(255, 127, 4);
         assert_eq!("ff7f04", c.to_hex_string(false));
         assert_eq!("#ff7f04", c.to_hex_string(true));
+
+        let c = RGBA::with_alpha(255, 127, 4, 0.5);
+        assert_eq!("#ff7f0480", c.to_hex_string(true));
+    }
+
+    #[test]
+    fn to_hex8_string() {
+        assert_eq!("#ff7f04ff", RGBA::new(255, 127, 4).to_hex8_string(true));
+        assert_eq!(
+            "ff7f0400",
+            RGBA::with_alpha(255, 127, 4, 0.0).to_hex8_string(false)
+        );
+    }
+
+    #[test]
+    fn to_hex_short_string() {
+        assert_eq!("#f07", RGBA::new(255, 0, 119).to_hex_short_string(true));
+        assert_eq!(
+            "f078",
+            RGBA::with_alpha(255, 0, 119, 0.533).to_hex_short_string(false)
+        );
+        assert_eq!("#ff7f04", RGBA::new(255, 127, 4).to_hex_short_string(true));
+        assert_eq!(
+            "#ff007780",
+            RGBA::with_alpha(255, 0, 119, 0.5).to_hex_short_string(true)
+        );
+    }
+
+    #[test]
+    fn to_argb_and_rgba_u32() {
+        let c = RGBA::with_alpha(0xf4, 0x23, 0x0f, 0.5);
+        assert_eq!(0x80f4230f, c.to_argb_u32());
+        assert_eq!(0xf4230f80, c.to_rgba_u32());
+        assert_eq!(0xffff0000, RGBA::new(255, 0, 0).to_argb_u32());
     }
 
     fn rgb(r: u8, g: u8, b: u8) -> Color {
@@ -929,4 +1142,51 @@ mod tests {
         // comma separators not allowed
         assert_eq!(None, parse_color("color(srgb-linear 0.3, 0.5, 0.7)"));
     }
+
+    #[test]
+    fn parse_hex_integer_syntax() {
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("0xff8000"));
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("0XFF8000"));
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("0xffff8000"));
+        assert_eq!(Some(rgba(255, 128, 0, 0.4)), parse_color("0x66ff8000"));
+
+        assert_eq!(None, parse_color("0xff80"));
+        assert_eq!(None, parse_color("0xff80001"));
+    }
+
+    #[test]
+    fn parse_signed_argb_integer_syntax() {
+        assert_eq!(Some(rgb(255, 0, 0)), parse_color("-65536"));
+        assert_eq!(Some(rgb(0, 0, 0)), parse_color("-16777216"));
+        assert_eq!(Some(rgba(255, 128, 0, 0.6)), parse_color("-1711308800"));
+
+        assert_eq!(None, parse_color("-2147483649"));
+        assert_eq!(None, parse_color("-"));
+    }
+
+    #[test]
+    fn parse_x11_syntax() {
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("rgb:ff/80/00"));
+        assert_eq!(Some(rgb(255, 136, 0)), parse_color("rgb:f/8/0"));
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("rgb:ffff/8080/0000"));
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("RGB:FF/80/00"));
+        assert_eq!(Some(rgb(255, 128, 0)), parse_color("rgbi:1.0/0.5/0"));
+
+        assert_eq!(None, parse_color("rgb:fffff/0/0"));
+        assert_eq!(None, parse_color("rgb:ff/80"));
+        assert_eq!(None, parse_color("rgbi:1.0/0.5"));
+    }
+
+    #[test]
+    fn parse_float_tuple_syntax() {
+        assert_eq!(Some(rgb(51, 102, 153)), parse_color("(0.2, 0.4, 0.6)"));
+        assert_eq!(Some(rgb(51, 102, 153)), parse_color("( 0.2 0.4 0.6 )"));
+        assert_eq!(
+            Some(rgba(51, 102, 153, 0.5)),
+            parse_color("(0.2, 0.4, 0.6, 0.5)")
+        );
+
+        assert_eq!(None, parse_color("(0.2, 0.4)"));
+        assert_eq!(None, parse_color("(0.2, 0.4, 0.6"));
+    }
 }
@@ -0,0 +1,142 @@
+use std::fmt;
+
+use crate::{
+    colorspace::ColorSpace,
+    convert::{gam_srgb, lin_srgb},
+    helper::{interpolate, interpolate_premultiplied, HueInterpolation},
+    types::Scalar,
+    Color, Fraction, RGBA,
+};
+
+/// Linear-light sRGB: the sRGB primaries without the gamma encoding ("transfer function").
+/// The components are proportional to the amount of emitted light, which makes this color space
+/// suitable for physically mixing light.
+///
+/// See: <https://www.w3.org/TR/css-color-4/#predefined-sRGB-linear>
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearRGBA {
+    pub r: Scalar,
+    pub g: Scalar,
+    pub b: Scalar,
+    pub alpha: Scalar,
+}
+
+impl LinearRGBA {
+    #[inline]
+    pub fn new(r: Scalar, g: Scalar, b: Scalar) -> Self {
+        Self::with_alpha(r, g, b, 1.0)
+    }
+
+    #[inline]
+    pub fn with_alpha(r: Scalar, g: Scalar, b: Scalar, alpha: Scalar) -> Self {
+        Self { r, g, b, alpha }
+    }
+}
+
+impl ColorSpace for LinearRGBA {
+    fn from_color(c: &Color) -> Self {
+        Self::from(c)
+    }
+
+    fn into_color(self) -> Color {
+        Color::from(&self)
+    }
+
+    fn mix(&self, other: &Self, fraction: Fraction) -> Self {
+        Self {
+            r: interpolate(self.r, other.r, fraction),
+            g: interpolate(self.g, other.g, fraction),
+            b: interpolate(self.b, other.b, fraction),
+            alpha: interpolate(self.alpha, other.alpha, fraction),
+        }
+    }
+
+    fn mix_premultiplied(&self, other: &Self, fraction: Fraction, _hue: HueInterpolation) -> Self {
+        let (alpha_a, alpha_b) = (self.alpha, other.alpha);
+        Self {
+            r: interpolate_premultiplied(self.r, alpha_a, other.r, alpha_b, fraction),
+            g: interpolate_premultiplied(self.g, alpha_a, other.g, alpha_b, fraction),
+            b: interpolate_premultiplied(self.b, alpha_a, other.b, alpha_b, fraction),
+            alpha: interpolate(alpha_a, alpha_b, fraction),
+        }
+    }
+}
+
+impl From<&Color> for LinearRGBA {
+    fn from(color: &Color) -> Self {
+        let rgba = RGBA::<f64>::from(color);
+        let [r, g, b] = lin_srgb([rgba.r, rgba.g, rgba.b]);
+
+        LinearRGBA::with_alpha(r, g, b, rgba.alpha)
+    }
+}
+
+impl From<&LinearRGBA> for Color {
+    fn from(color: &LinearRGBA) -> Self {
+        let [r, g, b] = gam_srgb([color.r, color.g, color.b]);
+
+        Color::from_rgba_float(r, g, b, color.alpha)
+    }
+}
+
+impl fmt::Display for LinearRGBA {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "color(srgb-linear {r} {g} {b}",
+            r = self.r,
+            g = self.g,
+            b = self.b,
+        )?;
+        if self.alpha < 1.0 {
+            write!(f, " / {}", self.alpha)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn roundtrip() {
+        for color in [
+            Color::black(),
+            Color::white(),
+            Color::red(),
+            Color::from_rgba(12, 200, 87, 0.5),
+        ] {
+            let linear = LinearRGBA::from(&color);
+            assert_eq!(color, Color::from(&linear));
+        }
+    }
+
+    #[test]
+    fn conversion() {
+        let linear = LinearRGBA::from(&Color::from_rgb(128, 0, 255));
+        assert_relative_eq!(0.215861, linear.r, epsilon = 1e-6);
+        assert_relative_eq!(0.0, linear.g);
+        assert_relative_eq!(1.0, linear.b);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "color(srgb-linear 1 0 0)",
+            LinearRGBA::from(&Color::red()).to_string()
+        );
+        assert_eq!(
+            "color(srgb-linear 0 0 1 / 0.5)",
+            LinearRGBA::from(&Color::from_rgba(0, 0, 255, 0.5)).to_string()
+        );
+    }
+
+    #[test]
+    fn mix_light() {
+        // Mixing black and white light results in a gray that is lighter than 50% gray
+        let gray = Color::black().mix::<LinearRGBA>(&Color::white(), Fraction::from(0.5));
+        assert_eq!(Color::from_rgb(188, 188, 188), gray);
+    }
+}
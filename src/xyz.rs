@@ -2,13 +2,13 @@ use std::fmt;
 
 use nom::{
     branch::alt, bytes::complete::tag_no_case, character::complete::space1,
-    combinator::all_consuming, IResult,
+    combinator::all_consuming,
 };
 
 use crate::{
     convert::{gam_srgb, lin_srgb},
     matrix::mat3_dot,
-    parser::{css_color_function, number_or_percentage},
+    parser::{component, css_color_function, number_or_percentage, IResult},
     types::{Mat3, Scalar},
     Color, RGBA,
 };
@@ -85,11 +85,10 @@ pub(crate) fn parse_xyz_color(input: &str) -> IResult<&str, Color> {
 // illuminant, so we only support the `xyz-d65` color space here.
 fn parse_xyz_d65_color_space(input: &str) -> IResult<&str, Color> {
     fn xyz_components(input: &str) -> IResult<&str, Color> {
-        let (input, x) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, y) = number_or_percentage(input, 1.0)?;
-        let (input, _) = space1(input)?;
-        let (input, z) = number_or_percentage(input, 1.0)?;
+        let value = |input| number_or_percentage(input, 1.0);
+        let (input, x) = component("expected x (a number or a percentage)", space1, value)(input)?;
+        let (input, y) = component("expected y (a number or a percentage)", space1, value)(input)?;
+        let (input, z) = component("expected z (a number or a percentage)", space1, value)(input)?;
 
         let c = Color::from_xyz(x, y, z, 1.0);
         Ok((input, c))
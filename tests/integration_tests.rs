@@ -62,6 +62,29 @@ fn format_basic() {
         .stdout("rgb(255, 0, 0)\nrgb(0, 0, 255)\n");
 }
 
+#[test]
+fn background_color_argument() {
+    pastel()
+        .arg("--background")
+        .arg("-")
+        .arg("format")
+        .arg("hex")
+        .arg("rgba(255, 0, 0, 0.5)")
+        .write_stdin("white\n")
+        .assert()
+        .success()
+        .stdout("#ff7f7f\n");
+
+    pastel()
+        .arg("--background")
+        .arg("nocolor")
+        .arg("format")
+        .arg("hex")
+        .arg("red")
+        .assert()
+        .failure();
+}
+
 #[test]
 fn pipe_into_format_command() {
     let first = pastel()
@@ -123,3 +146,823 @@ fn set_basic() {
         .success()
         .stdout("hsl(0,0.0%,50.0%)\n");
 }
+
+#[test]
+fn distinct_extend_keeps_existing_palette() {
+    let output = pastel()
+        .arg("distinct")
+        .arg("--extend")
+        .arg("2")
+        .arg("--algorithm")
+        .arg("greedy")
+        .write_stdin("red\nblue\n")
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "hsl(0,100.0%,50.0%)");
+    assert_eq!(lines[1], "hsl(240,100.0%,50.0%)");
+
+    let output = pastel()
+        .arg("distinct")
+        .arg("--extend")
+        .arg("1")
+        .arg("--colors")
+        .arg("red")
+        .arg("blue")
+        .arg("--algorithm")
+        .arg("greedy")
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "hsl(0,100.0%,50.0%)");
+    assert_eq!(lines[1], "hsl(240,100.0%,50.0%)");
+
+    pastel()
+        .arg("distinct")
+        .arg("--extend")
+        .arg("2")
+        .arg("red")
+        .arg("blue")
+        .assert()
+        .failure();
+
+    pastel()
+        .arg("distinct")
+        .arg("--extend")
+        .arg("2")
+        .arg("--palette")
+        .arg("palette.txt")
+        .arg("--colors")
+        .arg("red")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn distinct_algorithms() {
+    let distinct = |algorithm: &str| {
+        let output = pastel()
+            .arg("distinct")
+            .arg("3")
+            .arg("red")
+            .arg("--algorithm")
+            .arg(algorithm)
+            .arg("--restarts")
+            .arg("2")
+            .assert()
+            .success();
+        String::from_utf8(output.get_output().stdout.clone()).unwrap()
+    };
+
+    for algorithm in ["annealing", "greedy", "hybrid"] {
+        let stdout = distinct(algorithm);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", algorithm);
+        assert_eq!(lines[0], "hsl(0,100.0%,50.0%)", "{}", algorithm);
+    }
+
+    // the greedy algorithm is deterministic
+    assert_eq!(distinct("greedy"), distinct("greedy"));
+
+    pastel()
+        .arg("distinct")
+        .arg("3")
+        .arg("--algorithm")
+        .arg("kmeans")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn distinct_contrast_with() {
+    let output = pastel()
+        .arg("distinct")
+        .arg("3")
+        .arg("--contrast-with")
+        .arg("white")
+        .arg("--min-contrast")
+        .arg("4.5")
+        .arg("--algorithm")
+        .arg("greedy")
+        .assert()
+        .success();
+    let colors = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+
+    let output = pastel()
+        .arg("format")
+        .arg("luminance")
+        .write_stdin(colors)
+        .assert()
+        .success();
+    let luminances = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+
+    // a contrast ratio of 4.5 against white requires a luminance of at most 1.05 / 4.5 - 0.05
+    assert_eq!(luminances.lines().count(), 3);
+    for luminance in luminances.lines() {
+        assert!(luminance.parse::<f64>().unwrap() <= 0.184, "{}", luminance);
+    }
+
+    pastel()
+        .arg("distinct")
+        .arg("3")
+        .arg("--min-contrast")
+        .arg("4.5")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn gradient_stop_positions() {
+    pastel()
+        .arg("gradient")
+        .arg("-n")
+        .arg("3")
+        .arg("red")
+        .arg("blue@0.5")
+        .arg("blue")
+        .assert()
+        .success()
+        .stdout("hsl(0,100.0%,50.0%)\nhsl(240,100.0%,50.0%)\nhsl(240,100.0%,50.0%)\n");
+
+    pastel()
+        .arg("gradient")
+        .arg("red@x")
+        .arg("blue")
+        .assert()
+        .failure();
+
+    // the first and last color extend to the ends of the gradient
+    for stops in [
+        ["red", "blue@0.3"],
+        ["red@0.5", "blue"],
+        ["red@1", "blue@0"],
+    ] {
+        pastel()
+            .arg("gradient")
+            .arg("-n")
+            .arg("5")
+            .args(stops)
+            .assert()
+            .success();
+    }
+
+    // hard stops
+    pastel()
+        .arg("gradient")
+        .arg("-n")
+        .arg("5")
+        .arg("-s")
+        .arg("RGB")
+        .args(["red@0", "red@0.5", "blue@0.5", "blue@1"])
+        .assert()
+        .success()
+        .stdout(
+            "hsl(0,100.0%,50.0%)\nhsl(0,100.0%,50.0%)\nhsl(240,100.0%,50.0%)\n\
+             hsl(240,100.0%,50.0%)\nhsl(240,100.0%,50.0%)\n",
+        );
+
+    // midpoint hints must lie within their segment
+    pastel()
+        .arg("gradient")
+        .args(["red", "@0.7", "blue@0.5", "white"])
+        .assert()
+        .failure();
+    pastel()
+        .arg("gradient")
+        .args(["red", "blue", "@0.5"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn colormap_presets() {
+    pastel()
+        .arg("colormap")
+        .arg("viridis")
+        .arg("-n")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout("hsl(288,97.6%,16.7%)\nhsl(54,98.2%,56.9%)\n");
+
+    pastel()
+        .arg("colormap")
+        .arg("Set1")
+        .arg("-n")
+        .arg("20")
+        .assert()
+        .failure();
+
+    pastel().arg("colormap").arg("unknown").assert().failure();
+
+    for colorspace in ["Lab", "RGB"] {
+        let colormap = pastel()
+            .arg("colormap")
+            .arg("magma")
+            .arg("-n")
+            .arg("7")
+            .arg("--colorspace")
+            .arg(colorspace)
+            .assert()
+            .success();
+        pastel()
+            .arg("gradient")
+            .arg("--preset")
+            .arg("magma")
+            .arg("-n")
+            .arg("7")
+            .arg("--colorspace")
+            .arg(colorspace)
+            .assert()
+            .success()
+            .stdout(colormap.get_output().stdout.clone());
+    }
+}
+
+#[test]
+fn mix_hue_interpolation() {
+    pastel()
+        .arg("mix")
+        .arg("--colorspace=HSL")
+        .arg("--hue=longer")
+        .arg("red")
+        .arg("lime")
+        .assert()
+        .success()
+        .stdout("hsl(240,100.0%,50.0%)\n");
+}
+
+#[test]
+fn gradient_premultiplied_alpha() {
+    pastel()
+        .arg("gradient")
+        .arg("-n")
+        .arg("3")
+        .arg("--colorspace=RGB")
+        .arg("rgba(0, 0, 0, 0)")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("hsla(0,0.0%,0.0%,0)\nhsla(0,100.0%,50.0%,0.5)\nhsl(0,100.0%,50.0%)\n");
+}
+
+#[test]
+fn blend_modes() {
+    pastel()
+        .arg("blend")
+        .arg("multiply")
+        .arg("white")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("hsl(0,100.0%,50.0%)\n");
+
+    pastel()
+        .arg("blend")
+        .arg("difference")
+        .arg("white")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("hsl(180,100.0%,50.0%)\n");
+}
+
+#[test]
+fn flatten_alpha() {
+    pastel()
+        .arg("flatten")
+        .arg("--on")
+        .arg("black")
+        .arg("rgba(255, 0, 0, 0.5)")
+        .assert()
+        .success()
+        .stdout("hsl(0,100.0%,24.9%)\n");
+
+    pastel()
+        .arg("--background=white")
+        .arg("format")
+        .arg("hex")
+        .arg("rgba(255, 0, 0, 0.5)")
+        .assert()
+        .success()
+        .stdout("#ff7f7f\n");
+
+    pastel()
+        .arg("set")
+        .arg("alpha")
+        .arg("0.5")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("hsla(0,100.0%,50.0%,0.5)\n");
+}
+
+#[test]
+fn format_hex_alpha() {
+    let format = |format_type: &str, color: &str, expected: &str| {
+        pastel()
+            .arg("format")
+            .arg(format_type)
+            .arg(color)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", expected));
+    };
+
+    format("hex", "#ff0077", "#ff0077");
+    format("hex", "#ff007780", "#ff007780");
+    format("hex8", "#ff0077", "#ff0077ff");
+    format("hex-short", "#ff0077", "#f07");
+    format("hex-short", "#ff007788", "#f078");
+    format("hex-short", "#ff0078", "#ff0078");
+    format("hex-argb", "#ff007780", "0x80FF0077");
+    format("hex-rgba", "#ff007780", "0xFF007780");
+}
+
+#[test]
+fn format_code() {
+    let format = |format_type: &str, color: &str, expected: &str| {
+        pastel()
+            .arg("format")
+            .arg(format_type)
+            .arg(color)
+            .assert()
+            .success()
+            .stdout(format!("{}\n", expected));
+    };
+
+    format(
+        "uicolor",
+        "#ff0000",
+        "UIColor(red: 1.000, green: 0.000, blue: 0.000, alpha: 1.000)",
+    );
+    format(
+        "swiftui",
+        "#ff000080",
+        "Color(red: 1.000, green: 0.000, blue: 0.000, opacity: 0.502)",
+    );
+    format("compose", "#ff0000", "Color(0xFFFF0000)");
+    format(
+        "android-xml",
+        "#ff0000",
+        "<color name=\"red\">#FF0000</color>",
+    );
+    format(
+        "android-xml",
+        "#ff000080",
+        "<color name=\"red\">#80FF0000</color>",
+    );
+    format("css-var", "#ff0000", "--red: #ff0000;");
+    format("scss", "#ff0000", "$red: #ff0000;");
+    format("less", "#ff0000", "@red: #ff0000;");
+    format("tailwind", "#ff0000", "colors: {\n  'red': '#ff0000',\n}");
+
+    // variable names are unique within the palette
+    pastel()
+        .arg("format")
+        .arg("scss")
+        .arg("red")
+        .arg("#fe0000")
+        .arg("blue")
+        .assert()
+        .success()
+        .stdout("$red: #ff0000;\n$red-2: #fe0000;\n$blue: #0000ff;\n");
+    pastel()
+        .arg("format")
+        .arg("android-xml")
+        .arg("red")
+        .arg("#fe0000")
+        .assert()
+        .success()
+        .stdout("<color name=\"red\">#FF0000</color>\n<color name=\"red_2\">#FE0000</color>\n");
+}
+
+#[test]
+fn format_srgb_linear() {
+    pastel()
+        .arg("format")
+        .arg("srgb-linear")
+        .arg("red")
+        .arg("rgba(0, 0, 255, 0.5)")
+        .assert()
+        .success()
+        .stdout("color(srgb-linear 1 0 0)\ncolor(srgb-linear 0 0 1 / 0.5)\n");
+
+    // the output can be parsed again
+    pastel()
+        .arg("format")
+        .arg("hex")
+        .arg("color(srgb-linear 0.2158605 0 1)")
+        .assert()
+        .success()
+        .stdout("#8000ff\n");
+}
+
+#[test]
+fn format_template() {
+    pastel()
+        .arg("format")
+        .arg("--template")
+        .arg("{r},{g},{b} /* {name} */ {lch.c:.1}")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("255,0,0 /* red */ 104.6\n");
+
+    pastel()
+        .arg("format")
+        .arg("--template")
+        .arg("{foo}")
+        .arg("red")
+        .assert()
+        .failure();
+
+    pastel()
+        .arg("format")
+        .arg("--template")
+        .arg("{hex}")
+        .arg("red")
+        .arg("blue")
+        .assert()
+        .success()
+        .stdout("#ff0000\n#0000ff\n");
+
+    pastel()
+        .arg("format")
+        .arg("--template")
+        .arg("{name}")
+        .write_stdin("teal\n")
+        .assert()
+        .success()
+        .stdout("teal\n");
+
+    // a format type can not be combined with a template
+    pastel()
+        .arg("format")
+        .arg("rgb")
+        .arg("--template")
+        .arg("{hex}")
+        .arg("red")
+        .assert()
+        .failure();
+
+    pastel()
+        .arg("format")
+        .arg("foo")
+        .arg("red")
+        .assert()
+        .failure();
+
+    pastel()
+        .arg("format")
+        .arg("HSL-Hue")
+        .arg("red")
+        .assert()
+        .success()
+        .stdout("0\n");
+}
+
+#[test]
+fn tokens_dtcg() {
+    let tokens = r##"{
+  "color": {
+    "$type": "color",
+    "brand": { "$value": "#ff0000" },
+    "primary": { "$value": "{color.brand}" }
+  },
+  "spacing": { "$type": "dimension", "$value": "4px" }
+}"##;
+
+    pastel()
+        .arg("tokens")
+        .arg("--exec")
+        .arg("rotate 120")
+        .write_stdin(tokens)
+        .assert()
+        .success()
+        .stdout(
+            r##"{
+  "color": {
+    "$type": "color",
+    "brand": {
+      "$value": "#00ff00"
+    },
+    "primary": {
+      "$value": "{color.brand}"
+    }
+  },
+  "spacing": {
+    "$type": "dimension",
+    "$value": "4px"
+  }
+}
+"##,
+        );
+}
+
+#[test]
+fn tokens_style_dictionary() {
+    let tokens = r##"{
+  "color": {
+    "base": { "value": "blue" },
+    "link": { "value": "{color.base.value}", "comment": "Links" }
+  }
+}"##;
+
+    pastel()
+        .arg("tokens")
+        .arg("--resolve-aliases")
+        .arg("--output-format=dtcg")
+        .write_stdin(tokens)
+        .assert()
+        .success()
+        .stdout(
+            r##"{
+  "color": {
+    "base": {
+      "$value": "#0000ff"
+    },
+    "link": {
+      "$value": "#0000ff",
+      "$description": "Links"
+    }
+  }
+}
+"##,
+        );
+}
+
+#[test]
+fn tokens_unknown_format_type() {
+    let output = pastel()
+        .arg("tokens")
+        .arg("--to")
+        .arg("foo")
+        .write_stdin(r##"{ "red": { "$value": "#ff0000" } }"##)
+        .assert()
+        .code(1)
+        .get_output()
+        .stderr
+        .clone();
+
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains("Unknown format type 'foo'"));
+}
+
+#[test]
+fn replace_colors_in_text() {
+    pastel()
+        .arg("replace")
+        .arg("--exec")
+        .arg("rotate 120")
+        .arg("--names")
+        .write_stdin(".a { color: #F00; border: 1px solid rgb(255, 0, 0); } /* red */\n")
+        .assert()
+        .success()
+        .stdout(".a { color: #0F0; border: 1px solid rgb(0, 255, 0); } /* lime */\n");
+
+    pastel()
+        .arg("replace")
+        .arg("--to")
+        .arg("hex")
+        .write_stdin("red hsl(0, 100%, 50%)\n")
+        .assert()
+        .success()
+        .stdout("red #ff0000\n");
+
+    // source code is left alone
+    let code = "let red = rgb.red; // see #123\nclass=\"red_box\"\n";
+    pastel()
+        .arg("replace")
+        .arg("--exec")
+        .arg("darken 0.1")
+        .write_stdin(code)
+        .assert()
+        .success()
+        .stdout(code);
+
+    pastel()
+        .arg("replace")
+        .arg("--exec")
+        .arg("set alpha 0.25")
+        .write_stdin("a { color: rgb(255 0 0 / 50%); }\n")
+        .assert()
+        .success()
+        .stdout("a { color: rgb(255 0 0 / 25%); }\n");
+}
+
+#[test]
+fn grep_colors() {
+    pastel()
+        .arg("grep")
+        .arg("--names")
+        .write_stdin("a { color: #F00; }\nb { color: rgb(254, 1, 0); background: navy }\n")
+        .assert()
+        .success()
+        .stdout(
+            "(standard input):1:12: #F00 #ff0000 red\n\
+             (standard input):2:12: rgb(254, 1, 0) #fe0100 ~red\n\
+             (standard input):2:40: navy #000080 navy\n\
+             \n\
+             Near-duplicate colors (ΔE < 3):\n  \
+               #ff0000 (1x), #fe0100 (1x)\n",
+        );
+}
+
+#[test]
+fn grep_ignores_id_selectors() {
+    pastel()
+        .arg("grep")
+        .write_stdin("#fade > a, #add { color: #abc; }\n")
+        .assert()
+        .success()
+        .stdout("(standard input):1:26: #abc #aabbcc ~lightsteelblue\n");
+}
+
+#[test]
+fn color_parse_error_diagnostics() {
+    let stderr = |color: &str| {
+        let output = pastel()
+            .arg("format")
+            .arg("hex")
+            .arg(color)
+            .assert()
+            .failure()
+            .get_output()
+            .stderr
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    assert!(stderr("cornflowrblue").contains("Did you mean 'cornflowerblue'?"));
+    assert!(stderr("hsl(10, 50, 50%)").contains(
+        "hsl(): expected saturation (a percentage)\n\n    hsl(10, 50, 50%)\n            ^"
+    ));
+    assert!(stderr("rgb:ff/80").contains("expected blue (one to four hex digits)"));
+}
+
+#[test]
+fn css_relative_colors_and_color_mix() {
+    pastel()
+        .arg("format")
+        .arg("hex")
+        .arg("rgb(from #ff0000 r g calc(b + 20))")
+        .arg("color-mix(in srgb, red 40%, blue)")
+        .assert()
+        .success()
+        .stdout("#ff0014\n#660099\n");
+}
+
+#[test]
+fn stdin_error_policies() {
+    let input = "red\nno color\n# comment\n\n#00ff00, blue // two colors\n";
+    let run = |policy: &str, code: i32| {
+        let output = pastel()
+            .arg("--on-error")
+            .arg(policy)
+            .arg("format")
+            .arg("hex")
+            .write_stdin(input)
+            .assert()
+            .code(code)
+            .get_output()
+            .clone();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+
+    let (stdout, stderr) = run("fail", 1);
+    assert_eq!("#ff0000\n", stdout);
+    assert!(stderr.contains("Could not parse color on line 2, column 1"));
+
+    let (stdout, stderr) = run("skip", 3);
+    assert_eq!("#ff0000\n#00ff00\n#0000ff\n", stdout);
+    assert!(stderr.is_empty());
+
+    let (stdout, stderr) = run("warn", 3);
+    assert_eq!("#ff0000\n#00ff00\n#0000ff\n", stdout);
+    assert!(stderr.contains("line 2, column 1: could not parse 'no'"));
+    assert!(stderr.contains("line 2, column 4: could not parse 'color'"));
+
+    let (stdout, _) = run("passthrough", 3);
+    assert_eq!("#ff0000\nno color\n# comment\n\n#00ff00\n#0000ff\n", stdout);
+
+    pastel()
+        .arg("--on-error")
+        .arg("skip")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("red\n\n# comment\n")
+        .assert()
+        .success()
+        .stdout("#ff0000\n");
+
+    // the policy is applied to each word, the valid colors of the line are kept
+    for policy in ["skip", "warn"] {
+        pastel()
+            .arg("--on-error")
+            .arg(policy)
+            .arg("format")
+            .arg("hex")
+            .write_stdin("red nocolor blue\n")
+            .assert()
+            .code(3)
+            .stdout("#ff0000\n#0000ff\n");
+    }
+}
+
+#[test]
+fn colors_in_fields_and_json_lines() {
+    pastel()
+        .arg("--field")
+        .arg("2")
+        .arg("--delimiter")
+        .arg(",")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("primary,red,main\nsecondary,\"rgb(0, 0, 255)\",x\n")
+        .assert()
+        .success()
+        .stdout("primary,#ff0000,main\nsecondary,\"#0000ff\",x\n");
+
+    pastel()
+        .arg("--field")
+        .arg("2")
+        .arg("lighten")
+        .arg("0.1")
+        .write_stdin("primary  #ff0000  main\n")
+        .assert()
+        .success()
+        .stdout("primary  hsl(0,100.0%,60.0%)  main\n");
+
+    pastel()
+        .arg("--json-path")
+        .arg("theme.color")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("{\"name\":\"a\",\"theme\":{\"color\":\"red\"}}\n")
+        .assert()
+        .success()
+        .stdout("{\"name\":\"a\",\"theme\":{\"color\":\"#ff0000\"}}\n");
+
+    pastel()
+        .arg("--json-path")
+        .arg("/theme/color.primary")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("{\"theme\":{\"color.primary\":\"red\"}}\n")
+        .assert()
+        .success()
+        .stdout("{\"theme\":{\"color.primary\":\"#ff0000\"}}\n");
+
+    // a CSV header can be passed through
+    pastel()
+        .arg("--field")
+        .arg("2")
+        .arg("--delimiter")
+        .arg(",")
+        .arg("--on-error")
+        .arg("passthrough")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("name,color\nprimary,red\n")
+        .assert()
+        .code(3)
+        .stdout("name,color\nprimary,#ff0000\n");
+}
+
+#[test]
+fn parallel_processing_preserves_order() {
+    let input: String = (0..10_000)
+        .map(|i| format!("#{:06x}\n", i * 1601))
+        .collect();
+
+    pastel()
+        .arg("--jobs")
+        .arg("4")
+        .arg("format")
+        .arg("hex")
+        .write_stdin(input.clone())
+        .assert()
+        .success()
+        .stdout(input);
+
+    pastel()
+        .arg("--jobs")
+        .arg("4")
+        .arg("format")
+        .arg("hex")
+        .write_stdin("#000001\n#000002\nno color\n#000003\n")
+        .assert()
+        .code(1)
+        .stdout("#000001\n#000002\n");
+}